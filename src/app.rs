@@ -0,0 +1,167 @@
+use crate::assets::GameAssets;
+use crate::cli::LaunchOptions;
+use crate::game::{GameState, get_scaling};
+use crate::input::{FrameInput, InputSource, MacroquadInput, ScriptedInput};
+use crate::replay::Replay;
+use crate::rng::GameRng;
+use crate::snapshot::{self, SNAPSHOT_PATH};
+use crate::timestep::{FixedTimestep, SIM_DT};
+use crate::tuning::{TUNING_PATH, Tuning, TuningWatcher};
+use crate::ui::UI;
+use crate::waves::WaveScript;
+use macroquad::prelude::*;
+use std::path::{Path, PathBuf};
+
+// The windowed game: live or replayed input, fixed-step simulation, drawing.
+// Runs until the window is closed; returns early only if startup fails.
+pub async fn run(options: LaunchOptions) -> Result<(), String> {
+    // A replay brings its own seed, mode, tuning and wave script and replaces
+    // live input entirely
+    let replay = match &options.replay {
+        Some(path) => Some(
+            Replay::load(path)
+                .map_err(|err| format!("failed to load {}: {}", path.display(), err))?,
+        ),
+        None => None,
+    };
+
+    let assets = GameAssets::load().await;
+    let tuning_path = options
+        .tuning
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(TUNING_PATH));
+    let (seed, mode, tuning) = match &replay {
+        Some(replay) => (replay.seed, replay.mode, replay.tuning.clone()),
+        None => {
+            let mut tuning = Tuning::load_or_default(&tuning_path);
+            if let Some(difficulty) = &options.difficulty {
+                tuning.difficulty = difficulty.clone();
+            }
+            (
+                options.seed.unwrap_or_else(GameRng::seed_from_time),
+                options.mode.unwrap_or_default(),
+                tuning,
+            )
+        }
+    };
+    let mut game = GameState::new(seed, tuning);
+    game.mode = mode;
+    if let Some(replay) = &replay {
+        game.wave_script = replay.waves.clone();
+    } else if let Some(path) = &options.waves {
+        game.wave_script = WaveScript::load(path)
+            .map_err(|err| format!("failed to load {}: {}", path.display(), err))?;
+    }
+    let mut replay_input = replay.map(|replay| ScriptedInput::new(replay.frames));
+
+    // Changing numbers or jumping to a saved state mid-run would desync a
+    // replay, so hot reload and save games are only available in normal play
+    let free_play = options.replay.is_none() && options.record.is_none();
+    let mut tuning_watcher = free_play.then(|| TuningWatcher::new(tuning_path));
+    let save_path = options
+        .load
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(SNAPSHOT_PATH));
+
+    if let Some(path) = &options.load {
+        match snapshot::load(path) {
+            Ok(state) => game = state,
+            Err(err) => return Err(format!("failed to load {}: {}", path.display(), err)),
+        }
+    }
+
+    let mut input_source = MacroquadInput::new();
+    let mut timestep = FixedTimestep::new();
+    let mut pending_input = FrameInput::default();
+    let mut recording = options
+        .record
+        .as_ref()
+        .map(|_| Replay::new(seed, mode, game.tuning.clone(), game.wave_script.clone()));
+
+    let mut debug_colliders = false;
+
+    // Closing the window is handled below so a recording can be written first
+    prevent_quit();
+    show_mouse(false);
+
+    loop {
+        if is_quit_requested() {
+            if let (Some(replay), Some(path)) = (recording.as_ref(), options.record.as_ref()) {
+                save_replay(replay, path);
+            }
+            return Ok(());
+        }
+
+        if let Some(watcher) = tuning_watcher.as_mut()
+            && let Some(mut tuning) = watcher.poll(get_frame_time())
+        {
+            if let Some(difficulty) = &options.difficulty {
+                tuning.difficulty = difficulty.clone();
+            }
+            eprintln!("reloaded {}", watcher.path().display());
+            game.set_tuning(tuning);
+        }
+
+        if is_key_pressed(KeyCode::F3) {
+            debug_colliders = !debug_colliders;
+        }
+
+        if free_play && is_key_pressed(KeyCode::F5) {
+            match snapshot::save(&game, &save_path) {
+                Ok(()) => eprintln!("saved {}", save_path.display()),
+                Err(err) => eprintln!("failed to save {}: {}", save_path.display(), err),
+            }
+        }
+        if free_play && is_key_pressed(KeyCode::F9) {
+            match snapshot::load(&save_path) {
+                Ok(state) => game = state,
+                Err(err) => eprintln!("failed to load {}: {}", save_path.display(), err),
+            }
+        }
+
+        pending_input.merge(&input_source.poll());
+
+        // Simulation runs at a fixed rate regardless of the display refresh
+        for _ in 0..timestep.advance(get_frame_time()) {
+            if let Some(script) = replay_input.as_mut() {
+                if script.is_finished() {
+                    break;
+                }
+                game.update(SIM_DT, &script.poll());
+                continue;
+            }
+
+            let was_in_run = game.in_run();
+            game.update(SIM_DT, &pending_input);
+
+            if let (Some(replay), Some(path)) = (recording.as_mut(), options.record.as_ref()) {
+                replay.record(&pending_input);
+                // Save whenever a run ends (death, time up or quitting to the
+                // menu) so the file always holds the whole session
+                if was_in_run && !game.in_run() {
+                    save_replay(replay, path);
+                }
+            }
+
+            pending_input.clear_presses();
+        }
+
+        game.flush_effects(&assets);
+        // Kept outside the game so it survives restarts and loads
+        game.debug_colliders = debug_colliders;
+        game.draw(&assets, timestep.alpha());
+
+        if let Some(script) = replay_input.as_ref() {
+            let (scale, offset_x, offset_y) = get_scaling();
+            UI::draw_replay_label(script.is_finished(), scale, offset_x, offset_y, &assets);
+        }
+
+        next_frame().await;
+    }
+}
+
+fn save_replay(replay: &Replay, path: &Path) {
+    if let Err(err) = replay.save(path) {
+        eprintln!("failed to save replay {}: {}", path.display(), err);
+    }
+}
@@ -1,11 +1,14 @@
+use crate::game::SoundCue;
+#[cfg(feature = "audio")]
 use crate::sound_gen::WavGenerator;
-use macroquad::audio::{Sound, load_sound_from_bytes};
+#[cfg(feature = "audio")]
+use macroquad::audio::{PlaySoundParams, Sound, load_sound_from_bytes, play_sound};
 use macroquad::prelude::*;
+use std::ops::Range;
 
 // Embed assets at compile time
 const FONT_DATA: &[u8] = include_bytes!("../assets/font/kenny_future.ttf");
 
-
 // UI Assets
 const BAR_BG: &[u8] = include_bytes!("../assets/PNG/Grey/Default/bar_round_large.png");
 const BAR_RED: &[u8] = include_bytes!("../assets/PNG/Red/Default/bar_round_large.png");
@@ -31,39 +34,93 @@ const BODY_RED_R: &[u8] = include_bytes!("../assets/characters/PNG/Default/red_b
 const BODY_YELLOW_R: &[u8] =
     include_bytes!("../assets/characters/PNG/Default/yellow_body_rhombus.png");
 
+// `char_bodies` holds the six squircles first, then the six rhombuses
+pub const SQUIRCLE_BODIES: Range<usize> = 0..6;
+pub const RHOMBUS_BODIES: Range<usize> = 6..12;
+
 pub struct GameAssets {
     pub font: Font,
-  
+
     pub bar_bg: Texture2D,
     pub bar_red: Texture2D,
     pub bar_yellow: Texture2D,
 
     pub char_bodies: Vec<Texture2D>,
 
-    // Sounds
-    pub snd_click: Sound,
-    pub snd_start: Sound,
-    pub snd_over: Sound,
+    #[cfg(feature = "audio")]
+    pub sounds: Sounds,
 }
 
-impl GameAssets {
-    pub async fn load() -> Self {
-        let font = load_ttf_font_from_bytes(FONT_DATA).expect("Failed to load font");
-       
-        let bar_bg = Texture2D::from_file_with_format(BAR_BG, Some(ImageFormat::Png));
-        let bar_red = Texture2D::from_file_with_format(BAR_RED, Some(ImageFormat::Png));
-        let bar_yellow = Texture2D::from_file_with_format(BAR_YELLOW, Some(ImageFormat::Png));
+// Procedurally generated sound effects. Only built with the `audio` feature;
+// without it the game runs silent and needs no sound device or libasound.
+#[cfg(feature = "audio")]
+pub struct Sounds {
+    pub click: Sound,
+    pub zap: Sound,
+    pub start: Sound,
+    pub over: Sound,
+    pub pickup: Sound,
+    pub telegraph: Sound,
+    pub boss_death: Sound,
+}
 
-        // Procedural Sound Generation
-        let snd_click = load_sound_from_bytes(&WavGenerator::generate_beep(1000.0, 0.05, 0.5))
+#[cfg(feature = "audio")]
+impl Sounds {
+    async fn load() -> Self {
+        let click = load_sound_from_bytes(&WavGenerator::generate_beep(1000.0, 0.05, 0.5))
             .await
             .expect("Failed click gen");
-        let snd_start = load_sound_from_bytes(&WavGenerator::generate_beep(600.0, 0.5, 0.5))
+        let zap = load_sound_from_bytes(&WavGenerator::generate_beep(2000.0, 0.03, 0.4))
+            .await
+            .expect("Failed zap gen");
+        let start = load_sound_from_bytes(&WavGenerator::generate_beep(600.0, 0.5, 0.5))
             .await
             .expect("Failed start gen");
-        let snd_over = load_sound_from_bytes(&WavGenerator::generate_beep(300.0, 0.8, 0.5))
+        let over = load_sound_from_bytes(&WavGenerator::generate_beep(300.0, 0.8, 0.5))
             .await
             .expect("Failed over gen");
+        let pickup = load_sound_from_bytes(&WavGenerator::generate_beep(1400.0, 0.12, 0.4))
+            .await
+            .expect("Failed pickup gen");
+        let telegraph =
+            load_sound_from_bytes(&WavGenerator::generate_sweep(300.0, 900.0, 0.6, 0.25))
+                .await
+                .expect("Failed telegraph gen");
+        let boss_death = load_sound_from_bytes(&WavGenerator::generate_beep(110.0, 1.2, 0.6))
+            .await
+            .expect("Failed boss death gen");
+
+        Self {
+            click,
+            zap,
+            start,
+            over,
+            pickup,
+            telegraph,
+            boss_death,
+        }
+    }
+
+    fn get(&self, cue: SoundCue) -> &Sound {
+        match cue {
+            SoundCue::Start => &self.start,
+            SoundCue::Click => &self.click,
+            SoundCue::Zap => &self.zap,
+            SoundCue::GameOver => &self.over,
+            SoundCue::Pickup => &self.pickup,
+            SoundCue::BossDeath => &self.boss_death,
+            SoundCue::Telegraph => &self.telegraph,
+        }
+    }
+}
+
+impl GameAssets {
+    pub async fn load() -> Self {
+        let font = load_ttf_font_from_bytes(FONT_DATA).expect("Failed to load font");
+
+        let bar_bg = Texture2D::from_file_with_format(BAR_BG, Some(ImageFormat::Png));
+        let bar_red = Texture2D::from_file_with_format(BAR_RED, Some(ImageFormat::Png));
+        let bar_yellow = Texture2D::from_file_with_format(BAR_YELLOW, Some(ImageFormat::Png));
 
         let char_bodies = vec![
             Texture2D::from_file_with_format(BODY_BLUE, Some(ImageFormat::Png)),
@@ -82,14 +139,26 @@ impl GameAssets {
 
         Self {
             font,
-           
+
             bar_bg,
             bar_red,
             bar_yellow,
             char_bodies,
-            snd_click,
-            snd_start,
-            snd_over,
+            #[cfg(feature = "audio")]
+            sounds: Sounds::load().await,
         }
     }
+
+    pub fn play(&self, cue: SoundCue) {
+        #[cfg(feature = "audio")]
+        play_sound(
+            self.sounds.get(cue),
+            PlaySoundParams {
+                looped: false,
+                volume: 1.0,
+            },
+        );
+        #[cfg(not(feature = "audio"))]
+        let _ = cue;
+    }
 }
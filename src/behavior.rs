@@ -0,0 +1,280 @@
+use crate::assets::{RHOMBUS_BODIES, SQUIRCLE_BODIES};
+use crate::popup::BodyShape;
+use crate::rng::GameRng;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Body colours in `GameAssets::char_bodies` order, per shape
+const BLUE: usize = 0;
+const GREEN: usize = 1;
+const PINK: usize = 2;
+const PURPLE: usize = 3;
+const RED: usize = 4;
+const YELLOW: usize = 5;
+
+const ZIGZAG_FREQUENCY: f32 = 6.0; // Radians per second
+const ZIGZAG_SWAY: f32 = 0.9; // Sideways speed relative to forward speed
+
+const ORBIT_RADIUS: f32 = 130.0;
+const ORBIT_TIME: f32 = 3.0; // Seconds circling before the dive
+const ORBIT_ANGULAR_SPEED: f32 = 1.8;
+const ORBIT_DIVE_SPEED: f32 = 1.6;
+
+const DASH_WAIT: f32 = 1.2;
+const DASH_TIME: f32 = 0.45;
+const DASH_CREEP_SPEED: f32 = 0.2;
+const DASH_SPEED: f32 = 3.5;
+
+const FLANK_MAX_LEAD: f32 = 1.0; // Longest look-ahead in seconds
+
+// What a behaviour gets to look at when deciding where to go
+pub struct SteerContext {
+    pub pos: Vec2, // Popup centre
+    pub speed: f32,
+    pub target: Vec2, // Cursor
+    pub target_velocity: Vec2,
+    pub dt: f32,
+}
+
+impl SteerContext {
+    fn to_target(&self) -> (Vec2, f32) {
+        let delta = self.target - self.pos;
+        let dist = delta.length();
+        if dist > 1.0 {
+            (delta / dist, dist)
+        } else {
+            (Vec2::ZERO, dist)
+        }
+    }
+}
+
+// A movement strategy. Returns the popup's velocity for this step.
+pub trait Behavior {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2;
+}
+
+// Straight-line chase, the original popup movement
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Chaser;
+
+impl Behavior for Chaser {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2 {
+        ctx.to_target().0 * ctx.speed
+    }
+}
+
+// Chases while swaying side to side
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ZigZagger {
+    pub phase: f32,
+}
+
+impl Behavior for ZigZagger {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2 {
+        self.phase += ctx.dt * ZIGZAG_FREQUENCY;
+        let (dir, _) = ctx.to_target();
+        let side = vec2(-dir.y, dir.x);
+        (dir + side * self.phase.sin() * ZIGZAG_SWAY) * ctx.speed
+    }
+}
+
+// Circles the cursor at a distance, then dives straight in
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Orbiter {
+    pub angle: Option<f32>, // Set on the first step from where the popup is
+    pub orbit_left: f32,
+}
+
+impl Behavior for Orbiter {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2 {
+        let (dir, _) = ctx.to_target();
+        if self.orbit_left <= 0.0 {
+            return dir * ctx.speed * ORBIT_DIVE_SPEED;
+        }
+        self.orbit_left -= ctx.dt;
+
+        let from_target = ctx.pos - ctx.target;
+        let angle = self
+            .angle
+            .get_or_insert_with(|| from_target.y.atan2(from_target.x));
+        *angle += ORBIT_ANGULAR_SPEED * ctx.dt;
+
+        let slot = ctx.target + vec2(angle.cos(), angle.sin()) * ORBIT_RADIUS;
+        let delta = slot - ctx.pos;
+        let max_step = ctx.speed * 1.3;
+        if delta.length() > max_step * ctx.dt {
+            delta.normalize() * max_step
+        } else {
+            delta / ctx.dt.max(f32::EPSILON)
+        }
+    }
+}
+
+// Creeps while winding up, then lunges in a locked direction
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Dasher {
+    pub timer: f32,
+    pub dash_dir: Option<(f32, f32)>, // Some while lunging
+}
+
+impl Behavior for Dasher {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2 {
+        self.timer += ctx.dt;
+        match self.dash_dir {
+            Some((dx, dy)) => {
+                if self.timer >= DASH_TIME {
+                    self.dash_dir = None;
+                    self.timer = 0.0;
+                }
+                vec2(dx, dy) * ctx.speed * DASH_SPEED
+            }
+            None => {
+                let (dir, _) = ctx.to_target();
+                if self.timer >= DASH_WAIT {
+                    self.dash_dir = Some((dir.x, dir.y));
+                    self.timer = 0.0;
+                }
+                dir * ctx.speed * DASH_CREEP_SPEED
+            }
+        }
+    }
+}
+
+// Heads for where the cursor is going to be rather than where it is
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Flanker;
+
+impl Behavior for Flanker {
+    fn steer(&mut self, ctx: &SteerContext) -> Vec2 {
+        let (_, dist) = ctx.to_target();
+        let lead = (dist / ctx.speed.max(1.0)).min(FLANK_MAX_LEAD);
+        let predicted = ctx.target + ctx.target_velocity * lead;
+        let delta = predicted - ctx.pos;
+        if delta.length() > 1.0 {
+            delta.normalize() * ctx.speed
+        } else {
+            Vec2::ZERO
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BehaviorKind {
+    Chaser,
+    ZigZagger,
+    Orbiter,
+    Dasher,
+    Flanker,
+}
+
+// Relative spawn weights when a wave does not ask for a specific kind
+const SPAWN_WEIGHTS: [(BehaviorKind, u32); 5] = [
+    (BehaviorKind::Chaser, 4),
+    (BehaviorKind::ZigZagger, 2),
+    (BehaviorKind::Orbiter, 1),
+    (BehaviorKind::Dasher, 1),
+    (BehaviorKind::Flanker, 1),
+];
+
+impl BehaviorKind {
+    // Squircles move predictably, rhombuses have a trick; the colour tells
+    // which one.
+    pub fn shape(self) -> BodyShape {
+        match self {
+            BehaviorKind::Chaser | BehaviorKind::ZigZagger => BodyShape::Squircle,
+            BehaviorKind::Orbiter | BehaviorKind::Dasher | BehaviorKind::Flanker => {
+                BodyShape::Rhombus
+            }
+        }
+    }
+
+    // Indices into `GameAssets::char_bodies` this kind may wear
+    pub fn bodies(self) -> Vec<usize> {
+        let (range, colours): (_, &[usize]) = match self {
+            BehaviorKind::Chaser => (SQUIRCLE_BODIES, &[BLUE, PINK, PURPLE, RED, YELLOW]),
+            BehaviorKind::ZigZagger => (SQUIRCLE_BODIES, &[GREEN]),
+            BehaviorKind::Orbiter => (RHOMBUS_BODIES, &[BLUE, PURPLE]),
+            BehaviorKind::Dasher => (RHOMBUS_BODIES, &[PINK, RED]),
+            BehaviorKind::Flanker => (RHOMBUS_BODIES, &[GREEN, YELLOW]),
+        };
+        colours.iter().map(|c| range.start + c).collect()
+    }
+
+    // Hit point multiplier; the trickier rhombuses take more to bring down
+    pub fn toughness(self) -> f32 {
+        match self {
+            BehaviorKind::Chaser | BehaviorKind::ZigZagger | BehaviorKind::Flanker => 1.0,
+            BehaviorKind::Orbiter | BehaviorKind::Dasher => 1.5,
+        }
+    }
+
+    // Weighted random kind that fits the requested shape
+    pub fn pick(shape: BodyShape, rng: &mut GameRng) -> Self {
+        let allowed: Vec<_> = SPAWN_WEIGHTS
+            .iter()
+            .filter(|(kind, _)| shape == BodyShape::Any || kind.shape() == shape)
+            .collect();
+        let total: u32 = allowed.iter().map(|(_, w)| w).sum();
+
+        let mut roll = rng.gen_range_usize(0, total as usize) as u32;
+        for (kind, weight) in allowed.iter() {
+            if roll < *weight {
+                return *kind;
+            }
+            roll -= weight;
+        }
+        BehaviorKind::Chaser
+    }
+}
+
+// Per-popup behaviour state. An enum rather than a boxed trait object so
+// popups stay cloneable and can be saved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PopupBehavior {
+    Chaser(Chaser),
+    ZigZagger(ZigZagger),
+    Orbiter(Orbiter),
+    Dasher(Dasher),
+    Flanker(Flanker),
+}
+
+impl PopupBehavior {
+    pub fn new(kind: BehaviorKind, rng: &mut GameRng) -> Self {
+        match kind {
+            BehaviorKind::Chaser => PopupBehavior::Chaser(Chaser),
+            BehaviorKind::ZigZagger => PopupBehavior::ZigZagger(ZigZagger {
+                phase: rng.gen_range_f32(0.0, 2.0 * std::f32::consts::PI),
+            }),
+            BehaviorKind::Orbiter => PopupBehavior::Orbiter(Orbiter {
+                angle: None,
+                orbit_left: ORBIT_TIME,
+            }),
+            BehaviorKind::Dasher => PopupBehavior::Dasher(Dasher {
+                timer: rng.gen_range_f32(0.0, DASH_WAIT),
+                dash_dir: None,
+            }),
+            BehaviorKind::Flanker => PopupBehavior::Flanker(Flanker),
+        }
+    }
+
+    pub fn kind(&self) -> BehaviorKind {
+        match self {
+            PopupBehavior::Chaser(_) => BehaviorKind::Chaser,
+            PopupBehavior::ZigZagger(_) => BehaviorKind::ZigZagger,
+            PopupBehavior::Orbiter(_) => BehaviorKind::Orbiter,
+            PopupBehavior::Dasher(_) => BehaviorKind::Dasher,
+            PopupBehavior::Flanker(_) => BehaviorKind::Flanker,
+        }
+    }
+
+    pub fn as_behavior_mut(&mut self) -> &mut dyn Behavior {
+        match self {
+            PopupBehavior::Chaser(b) => b,
+            PopupBehavior::ZigZagger(b) => b,
+            PopupBehavior::Orbiter(b) => b,
+            PopupBehavior::Dasher(b) => b,
+            PopupBehavior::Flanker(b) => b,
+        }
+    }
+}
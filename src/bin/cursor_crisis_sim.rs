@@ -0,0 +1,405 @@
+// Headless balance simulator: plays many games with a bot, no window or audio,
+// and prints the distribution of the results.
+//
+//   cursor_crisis_sim [--games N] [--seed S] [--bot idle|kite] [--max-time SECS]
+//                     [--tuning FILE] [--difficulty easy|normal|hard] [--waves FILE]
+//                     [--mode endless|time-attack|one-hit|zen] [--csv]
+//
+// Needs no sound device: build it with --no-default-features to leave the
+// audio backend (and libasound) out entirely.
+
+use cursor_crisis::difficulty::PRESET_NAMES;
+use cursor_crisis::game::{GameState, RunStats, VIRTUAL_H, VIRTUAL_W};
+use cursor_crisis::input::FrameInput;
+use cursor_crisis::modes::GameMode;
+use cursor_crisis::timestep::SIM_DT;
+use cursor_crisis::tuning::{TUNING_PATH, Tuning};
+use cursor_crisis::waves::WaveScript;
+use std::path::Path;
+use std::thread;
+
+const USAGE: &str = "usage: cursor_crisis_sim [--games N] [--seed S] [--bot idle|kite] \
+                     [--max-time SECS] [--tuning FILE] [--difficulty NAME] \
+                     [--waves FILE] [--mode NAME] [--csv]";
+
+// How fast the bot can move the cursor, in virtual pixels per second
+const BOT_SPEED: f32 = 450.0;
+// Popups a charged blast has to be able to catch before the kite bot holds
+const CHARGE_TARGET: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BotKind {
+    // Stays in the centre and explodes whenever something is in range
+    Idle,
+    // Steers away from popups and walls, explodes when it can catch a group
+    // or the boss and zaps a lone popup that reaches it. Charges up when a
+    // bigger blast would catch a crowd the plain one misses.
+    Kite,
+}
+
+impl BotKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "idle" => Some(BotKind::Idle),
+            "kite" => Some(BotKind::Kite),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            BotKind::Idle => "idle",
+            BotKind::Kite => "kite",
+        }
+    }
+}
+
+struct Bot {
+    kind: BotKind,
+    x: f32,
+    y: f32,
+}
+
+impl Bot {
+    fn new(kind: BotKind) -> Self {
+        Self {
+            kind,
+            x: VIRTUAL_W / 2.0,
+            y: VIRTUAL_H / 2.0,
+        }
+    }
+
+    fn decide(&mut self, game: &GameState, dt: f32) -> FrameInput {
+        if self.kind == BotKind::Kite {
+            self.steer_away(game, dt);
+        }
+
+        let radius_sq = game.tuning.explosion_radius * game.tuning.explosion_radius;
+        let pulse_aware = game.tuning.pulse_hitboxes;
+        let in_range = self.count_within(game, game.tuning.explosion_radius);
+        let touching = game
+            .popups
+            .iter()
+            .any(|p| p.hit(self.x, self.y, pulse_aware));
+        let boss_in_range = game
+            .boss
+            .as_ref()
+            .is_some_and(|b| !b.shielded() && b.dist_sq_to(self.x, self.y) <= radius_sq);
+
+        let wants_explosion = match self.kind {
+            BotKind::Idle => in_range > 0 || boss_in_range,
+            BotKind::Kite => in_range >= 3 || touching || boss_in_range,
+        };
+
+        if self.kind == BotKind::Kite {
+            if game.charge.is_some() {
+                let level = game.charge_level();
+                let caught = self.count_within(game, game.charged_radius(level));
+                let release = touching
+                    || caught >= CHARGE_TARGET
+                    || level >= 1.0
+                    || game.charged_cost(level) >= game.energy * 0.95;
+                return FrameInput {
+                    cursor_x: self.x,
+                    cursor_y: self.y,
+                    explode: release,
+                    charge: !release,
+                    ..Default::default()
+                };
+            }
+            if !wants_explosion
+                && game.energy >= game.charged_cost(1.0)
+                && self.count_within(game, game.charged_radius(1.0)) >= CHARGE_TARGET
+            {
+                return FrameInput {
+                    cursor_x: self.x,
+                    cursor_y: self.y,
+                    charge: true,
+                    ..Default::default()
+                };
+            }
+        }
+
+        let can_explode = game.energy >= game.tuning.energy_cost;
+        let wants_zap = self.kind == BotKind::Kite && touching && in_range < 3 && !can_explode;
+
+        FrameInput {
+            cursor_x: self.x,
+            cursor_y: self.y,
+            explode: wants_explosion && can_explode,
+            zap: wants_zap && game.energy >= game.tuning.zap_cost,
+            ..Default::default()
+        }
+    }
+
+    fn count_within(&self, game: &GameState, radius: f32) -> usize {
+        let pulse_aware = game.tuning.pulse_hitboxes;
+        game.popups
+            .iter()
+            .filter(|p| p.dist_sq_to(self.x, self.y, pulse_aware) <= radius * radius)
+            .count()
+    }
+
+    fn steer_away(&mut self, game: &GameState, dt: f32) {
+        let mut push_x = 0.0;
+        let mut push_y = 0.0;
+
+        for popup in game.popups.iter() {
+            let dx = self.x - (popup.x + popup.w / 2.0);
+            let dy = self.y - (popup.y + popup.h / 2.0);
+            let dist_sq = (dx * dx + dy * dy).max(1.0);
+            push_x += dx / dist_sq;
+            push_y += dy / dist_sq;
+        }
+        // The boss counts for several popups
+        if let Some(boss) = game.boss.as_ref() {
+            let dx = self.x - boss.x;
+            let dy = self.y - boss.y;
+            let dist_sq = (dx * dx + dy * dy).max(1.0);
+            push_x += 4.0 * dx / dist_sq;
+            push_y += 4.0 * dy / dist_sq;
+        }
+
+        // Walls push back so the bot does not get cornered
+        let margin = 60.0;
+        push_x += 1.0 / self.x.max(1.0) - 1.0 / (VIRTUAL_W - self.x).max(1.0);
+        push_y += 1.0 / self.y.max(1.0) - 1.0 / (VIRTUAL_H - self.y).max(1.0);
+
+        let len = (push_x * push_x + push_y * push_y).sqrt();
+        if len > f32::EPSILON {
+            self.x += push_x / len * BOT_SPEED * dt;
+            self.y += push_y / len * BOT_SPEED * dt;
+        }
+        self.x = self.x.clamp(margin, VIRTUAL_W - margin);
+        self.y = self.y.clamp(margin, VIRTUAL_H - margin);
+    }
+}
+
+struct SimOptions {
+    games: u32,
+    seed: u64,
+    bot: BotKind,
+    max_time: f32,
+    tuning: Tuning,
+    waves: WaveScript,
+    mode: GameMode,
+    csv: bool,
+}
+
+impl SimOptions {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            games: 1000,
+            seed: 1,
+            bot: BotKind::Kite,
+            max_time: 300.0,
+            tuning: Tuning::load_or_default(Path::new(TUNING_PATH)),
+            waves: WaveScript::default(),
+            mode: GameMode::Endless,
+            csv: false,
+        };
+        let mut difficulty = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or(format!("{} needs a value", flag));
+            match arg.as_str() {
+                "--games" => {
+                    let v = value("--games")?;
+                    options.games = v
+                        .parse()
+                        .map_err(|_| format!("invalid game count '{}'", v))?;
+                }
+                "--seed" => {
+                    let v = value("--seed")?;
+                    options.seed = v.parse().map_err(|_| format!("invalid seed '{}'", v))?;
+                }
+                "--bot" => {
+                    let v = value("--bot")?;
+                    options.bot = BotKind::parse(&v).ok_or(format!("unknown bot '{}'", v))?;
+                }
+                "--max-time" => {
+                    let v = value("--max-time")?;
+                    options.max_time = v.parse().map_err(|_| format!("invalid time '{}'", v))?;
+                }
+                "--tuning" => {
+                    let v = value("--tuning")?;
+                    options.tuning =
+                        Tuning::load(Path::new(&v)).map_err(|err| format!("{}: {}", v, err))?;
+                }
+                "--difficulty" => {
+                    let v = value("--difficulty")?;
+                    if !PRESET_NAMES.contains(&v.as_str()) {
+                        return Err(format!("unknown difficulty '{}'", v));
+                    }
+                    difficulty = Some(v);
+                }
+                "--waves" => {
+                    let v = value("--waves")?;
+                    options.waves =
+                        WaveScript::load(Path::new(&v)).map_err(|err| format!("{}: {}", v, err))?;
+                }
+                "--mode" => {
+                    let v = value("--mode")?;
+                    options.mode = GameMode::parse(&v).ok_or(format!("unknown mode '{}'", v))?;
+                }
+                "--csv" => options.csv = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        // Applied last so it wins over whatever the tuning file says
+        if let Some(difficulty) = difficulty {
+            options.tuning.difficulty = difficulty;
+        }
+
+        Ok(options)
+    }
+}
+
+struct GameResult {
+    seed: u64,
+    score: u32,
+    wave: u32,
+    stats: RunStats,
+}
+
+fn run_game(seed: u64, options: &SimOptions) -> GameResult {
+    let mut game = GameState::new(seed, options.tuning.clone());
+    game.wave_script = options.waves.clone();
+    game.mode = options.mode;
+    let mut bot = Bot::new(options.bot);
+    game.start_run();
+
+    while !game.is_game_over() && game.stats.time_alive < options.max_time {
+        let input = bot.decide(&game, SIM_DT);
+        game.update(SIM_DT, &input);
+        game.discard_effects();
+    }
+
+    GameResult {
+        seed,
+        score: game.score,
+        wave: game.waves.wave_number,
+        stats: game.stats,
+    }
+}
+
+fn run_all(options: &SimOptions) -> Vec<GameResult> {
+    let seeds: Vec<u64> = (0..options.games as u64)
+        .map(|i| options.seed.wrapping_add(i))
+        .collect();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = seeds.len().div_ceil(workers).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = seeds
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&seed| run_game(seed, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("simulation thread panicked"))
+            .collect()
+    })
+}
+
+fn print_distribution(label: &str, mut values: Vec<f32>) {
+    if values.is_empty() {
+        return;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let percentile = |p: f32| values[((values.len() - 1) as f32 * p).round() as usize];
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+
+    println!(
+        "{:<12} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+        label,
+        mean,
+        values[0],
+        percentile(0.1),
+        percentile(0.5),
+        percentile(0.9),
+        values[values.len() - 1],
+    );
+}
+
+fn main() {
+    let options = match SimOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let results = run_all(&options);
+
+    if options.csv {
+        println!("seed,survival_s,score,wave,bosses,explosions,starved_s");
+        for r in results.iter() {
+            println!(
+                "{},{:.3},{},{},{},{},{:.3}",
+                r.seed,
+                r.stats.time_alive,
+                r.score,
+                r.wave,
+                r.stats.bosses_defeated,
+                r.stats.explosions_used,
+                r.stats.energy_starved_time
+            );
+        }
+        return;
+    }
+
+    println!(
+        "bot: {}  mode: {}  difficulty: {}  games: {}  seeds: {}..{}  max time: {}s",
+        options.bot.name(),
+        options.mode.name(),
+        options.tuning.difficulty,
+        results.len(),
+        options.seed,
+        options.seed.wrapping_add(options.games as u64),
+        options.max_time
+    );
+    println!(
+        "{:<12} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9}",
+        "metric", "mean", "min", "p10", "p50", "p90", "max"
+    );
+    print_distribution(
+        "survival_s",
+        results.iter().map(|r| r.stats.time_alive).collect(),
+    );
+    print_distribution("score", results.iter().map(|r| r.score as f32).collect());
+    print_distribution("wave", results.iter().map(|r| r.wave as f32).collect());
+    print_distribution(
+        "bosses",
+        results
+            .iter()
+            .map(|r| r.stats.bosses_defeated as f32)
+            .collect(),
+    );
+    print_distribution(
+        "explosions",
+        results
+            .iter()
+            .map(|r| r.stats.explosions_used as f32)
+            .collect(),
+    );
+    print_distribution(
+        "starved_s",
+        results
+            .iter()
+            .map(|r| r.stats.energy_starved_time)
+            .collect(),
+    );
+}
@@ -0,0 +1,270 @@
+use crate::assets::{RHOMBUS_BODIES, SQUIRCLE_BODIES};
+use crate::collider::Collider;
+use crate::game::{VIRTUAL_H, VIRTUAL_W};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const BOSS_SIZE: f32 = 110.0;
+const BOSS_SPEED: f32 = 45.0;
+
+const SUMMON_TIME: f32 = 4.0;
+const SUMMON_INTERVAL: f32 = 1.25;
+pub const MINIONS_PER_SUMMON: u32 = 2;
+const WINDUP_TIME: f32 = 0.8;
+const CHARGE_TIME: f32 = 1.1;
+const CHARGE_SPEED: f32 = 380.0;
+const SHIELD_TIME: f32 = 3.0;
+const SHIELD_SPEED: f32 = 0.5; // Relative to BOSS_SPEED
+
+// Death shockwave; big enough to clear most of the arena around the boss
+pub const SHOCKWAVE_RADIUS: f32 = 220.0;
+pub const SHOCKWAVE_DAMAGE: f32 = 10.0;
+
+// Health drain while touching the boss, relative to `Tuning::damage_rate`
+pub const CONTACT_DAMAGE: f32 = 2.0;
+
+const HIT_FLASH_TIME: f32 = 0.15;
+const HIT_FLASH_COLOR: Color = Color::new(1.0, 0.35, 0.35, 1.0);
+
+// The boss is a big rhombus with a ring of squircles orbiting it
+const CORE_BODY: usize = RHOMBUS_BODIES.start + 3; // Purple
+const RING_SIZE: f32 = 34.0;
+const RING_SPIN: f32 = 1.2; // Radians per second
+const SHIELD_RING_SPIN: f32 = 4.0;
+
+// The boss loops Summon -> Windup -> Charge -> Shield -> Summon ...
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BossPhase {
+    Summon, // Drifts after the cursor calling in minions
+    Windup, // Stops and shakes before charging
+    Charge, // Rushes at where the cursor was when the windup ended
+    Shield, // Immune to damage, creeping slowly
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Boss {
+    pub x: f32, // Centre, in virtual space
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub hp: f32,
+    pub max_hp: f32,
+    pub phase: BossPhase,
+    pub phase_timer: f32,
+    pub hit_flash: f32,
+    minion_timer: f32,
+    charge_dir: (f32, f32),
+    spin: f32,
+}
+
+impl Boss {
+    pub fn new(x: f32, y: f32, max_hp: f32) -> Self {
+        Self {
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
+            hp: max_hp,
+            max_hp,
+            phase: BossPhase::Summon,
+            phase_timer: 0.0,
+            hit_flash: 0.0,
+            minion_timer: 0.0,
+            charge_dir: (0.0, 0.0),
+            spin: 0.0,
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        BOSS_SIZE / 2.0
+    }
+
+    pub fn shielded(&self) -> bool {
+        self.phase == BossPhase::Shield
+    }
+
+    fn enter(&mut self, phase: BossPhase) {
+        self.phase = phase;
+        self.phase_timer = 0.0;
+        self.minion_timer = 0.0;
+    }
+
+    // Advance the phase machine and move. Returns how many minions to spawn.
+    pub fn update(&mut self, dt: f32, target: Vec2) -> u32 {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+        self.phase_timer += dt;
+        self.hit_flash = (self.hit_flash - dt).max(0.0);
+        let spin_rate = if self.shielded() {
+            SHIELD_RING_SPIN
+        } else {
+            RING_SPIN
+        };
+        self.spin += spin_rate * dt;
+
+        let to_target = (target - vec2(self.x, self.y)).normalize_or_zero();
+        let mut minions = 0;
+        let mut velocity = Vec2::ZERO;
+
+        match self.phase {
+            BossPhase::Summon => {
+                velocity = to_target * BOSS_SPEED;
+                self.minion_timer += dt;
+                if self.minion_timer >= SUMMON_INTERVAL {
+                    self.minion_timer -= SUMMON_INTERVAL;
+                    minions = MINIONS_PER_SUMMON;
+                }
+                if self.phase_timer >= SUMMON_TIME {
+                    self.enter(BossPhase::Windup);
+                }
+            }
+            BossPhase::Windup => {
+                if self.phase_timer >= WINDUP_TIME {
+                    self.charge_dir = (to_target.x, to_target.y);
+                    self.enter(BossPhase::Charge);
+                }
+            }
+            BossPhase::Charge => {
+                velocity = vec2(self.charge_dir.0, self.charge_dir.1) * CHARGE_SPEED;
+                if self.phase_timer >= CHARGE_TIME {
+                    self.enter(BossPhase::Shield);
+                }
+            }
+            BossPhase::Shield => {
+                velocity = to_target * BOSS_SPEED * SHIELD_SPEED;
+                if self.phase_timer >= SHIELD_TIME {
+                    self.enter(BossPhase::Summon);
+                }
+            }
+        }
+
+        // Too big to leave the arena, even mid-charge
+        let r = self.radius();
+        self.x = (self.x + velocity.x * dt).clamp(r, VIRTUAL_W - r);
+        self.y = (self.y + velocity.y * dt).clamp(r, VIRTUAL_H - r);
+        minions
+    }
+
+    // Apply damage unless shielded; returns true when this kills the boss
+    pub fn take_damage(&mut self, amount: f32) -> bool {
+        if self.shielded() {
+            return false;
+        }
+        self.hp -= amount;
+        self.hit_flash = HIT_FLASH_TIME;
+        self.hp <= 0.0
+    }
+
+    // The core is a rhombus body, so it hits as a diamond like rhombus popups;
+    // the orbiting ring is decoration
+    pub fn collider(&self) -> Collider {
+        Collider::Diamond {
+            center: vec2(self.x, self.y),
+            radius: self.radius(),
+        }
+    }
+
+    // Squared distance from a point to the boss's body (0 inside)
+    pub fn dist_sq_to(&self, px: f32, py: f32) -> f32 {
+        self.collider().dist_sq_to(vec2(px, py))
+    }
+
+    pub fn hit(&self, px: f32, py: f32) -> bool {
+        self.collider().contains(vec2(px, py))
+    }
+
+    pub fn render_pos(&self, alpha: f32) -> (f32, f32) {
+        (
+            self.prev_x + (self.x - self.prev_x) * alpha,
+            self.prev_y + (self.y - self.prev_y) * alpha,
+        )
+    }
+
+    pub fn draw_scaled(
+        &self,
+        scale: f32,
+        ox: f32,
+        oy: f32,
+        alpha: f32,
+        char_textures: &[Texture2D],
+    ) {
+        let (mut x, mut y) = self.render_pos(alpha);
+        if self.phase == BossPhase::Windup {
+            // Shudder before the charge
+            x += (self.phase_timer * 90.0).sin() * 3.0;
+            y += (self.phase_timer * 70.0).cos() * 3.0;
+        }
+        let tint = if self.hit_flash > 0.0 {
+            HIT_FLASH_COLOR
+        } else {
+            WHITE
+        };
+
+        // Orbiting ring, pulled in tight while shielded
+        let ring_radius = if self.shielded() {
+            self.radius() * 0.85
+        } else {
+            self.radius() * 1.1
+        };
+        let ring_count = SQUIRCLE_BODIES.len();
+        for (i, body) in SQUIRCLE_BODIES.enumerate() {
+            let angle = self.spin + i as f32 / ring_count as f32 * std::f32::consts::TAU;
+            let rx = x + angle.cos() * ring_radius - RING_SIZE / 2.0;
+            let ry = y + angle.sin() * ring_radius - RING_SIZE / 2.0;
+            if let Some(tex) = char_textures.get(body) {
+                draw_texture_ex(
+                    tex,
+                    ox + rx * scale,
+                    oy + ry * scale,
+                    tint,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(RING_SIZE * scale, RING_SIZE * scale)),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        let size = BOSS_SIZE;
+        if let Some(tex) = char_textures.get(CORE_BODY) {
+            draw_texture_ex(
+                tex,
+                ox + (x - size / 2.0) * scale,
+                oy + (y - size / 2.0) * scale,
+                tint,
+                DrawTextureParams {
+                    dest_size: Some(vec2(size * scale, size * scale)),
+                    ..Default::default()
+                },
+            );
+        } else {
+            draw_circle(ox + x * scale, oy + y * scale, self.radius() * scale, GRAY);
+        }
+
+        if self.shielded() {
+            let pulse = 0.5 + 0.3 * (self.phase_timer * 8.0).sin();
+            draw_circle_lines(
+                ox + x * scale,
+                oy + y * scale,
+                (self.radius() + 8.0) * scale,
+                4.0,
+                Color::new(0.4, 0.75, 1.0, pulse),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_as_a_diamond() {
+        let boss = Boss::new(400.0, 300.0, 10.0);
+        let r = boss.radius();
+        assert!(boss.hit(400.0 + r * 0.9, 300.0));
+        // Inside the bounding circle, outside the rhombus
+        assert!(!boss.hit(400.0 + r * 0.6, 300.0 + r * 0.6));
+        assert!(boss.dist_sq_to(400.0 + r * 0.6, 300.0 + r * 0.6) > 0.0);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::difficulty::PRESET_NAMES;
+use crate::modes::GameMode;
+use std::path::PathBuf;
+
+// Command-line options for the game binary
+#[derive(Debug, Default)]
+pub struct LaunchOptions {
+    pub seed: Option<u64>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub tuning: Option<PathBuf>,
+    pub load: Option<PathBuf>,
+    pub difficulty: Option<String>, // Overrides the tuning file's difficulty; recorded in replays
+    pub waves: Option<PathBuf>,
+    pub mode: Option<GameMode>, // Preselected on the title screen
+}
+
+pub const USAGE: &str = "usage: cursor_crisis [--seed N] [--record FILE] [--replay FILE] \
+                         [--tuning FILE] [--load FILE] [--difficulty easy|normal|hard] \
+                         [--waves FILE] [--mode endless|time-attack|one-hit|zen]";
+
+impl LaunchOptions {
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    options.seed = Some(seed);
+                }
+                "--record" => {
+                    let value = args.next().ok_or("--record needs a file path")?;
+                    options.record = Some(PathBuf::from(value));
+                }
+                "--replay" => {
+                    let value = args.next().ok_or("--replay needs a file path")?;
+                    options.replay = Some(PathBuf::from(value));
+                }
+                "--tuning" => {
+                    let value = args.next().ok_or("--tuning needs a file path")?;
+                    options.tuning = Some(PathBuf::from(value));
+                }
+                "--load" => {
+                    let value = args.next().ok_or("--load needs a file path")?;
+                    options.load = Some(PathBuf::from(value));
+                }
+                "--difficulty" => {
+                    let value = args.next().ok_or("--difficulty needs a preset name")?;
+                    if !PRESET_NAMES.contains(&value.as_str()) {
+                        return Err(format!("unknown difficulty '{}'", value));
+                    }
+                    options.difficulty = Some(value);
+                }
+                "--waves" => {
+                    let value = args.next().ok_or("--waves needs a file path")?;
+                    options.waves = Some(PathBuf::from(value));
+                }
+                "--mode" => {
+                    let value = args.next().ok_or("--mode needs a mode name")?;
+                    let mode = GameMode::parse(&value)
+                        .ok_or_else(|| format!("unknown mode '{}'", value))?;
+                    options.mode = Some(mode);
+                }
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        if options.record.is_some() && options.replay.is_some() {
+            return Err("--record and --replay cannot be used together".to_owned());
+        }
+        if options.mode.is_some() && options.replay.is_some() {
+            return Err("--mode cannot be combined with --replay".to_owned());
+        }
+        // The replay carries the tuning (difficulty override included) and
+        // the wave script it was recorded with
+        if options.tuning.is_some() && options.replay.is_some() {
+            return Err("--tuning cannot be combined with --replay".to_owned());
+        }
+        if options.difficulty.is_some() && options.replay.is_some() {
+            return Err("--difficulty cannot be combined with --replay".to_owned());
+        }
+        if options.waves.is_some() && options.replay.is_some() {
+            return Err("--waves cannot be combined with --replay".to_owned());
+        }
+        if options.load.is_some() && (options.record.is_some() || options.replay.is_some()) {
+            return Err("--load cannot be combined with --record or --replay".to_owned());
+        }
+
+        Ok(options)
+    }
+}
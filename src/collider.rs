@@ -0,0 +1,127 @@
+use macroquad::prelude::*;
+
+// Hit shape of something in the arena, in virtual space. Cursor damage,
+// zaps and explosions all test against these so what you see is what hits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Collider {
+    Circle { center: Vec2, radius: f32 },
+    // Square rotated 45 degrees; `radius` is centre to corner
+    Diamond { center: Vec2, radius: f32 },
+}
+
+impl Collider {
+    pub fn center(&self) -> Vec2 {
+        match *self {
+            Collider::Circle { center, .. } | Collider::Diamond { center, .. } => center,
+        }
+    }
+
+    pub fn contains(&self, p: Vec2) -> bool {
+        match *self {
+            Collider::Circle { center, radius } => p.distance_squared(center) <= radius * radius,
+            Collider::Diamond { center, radius } => {
+                let d = (p - center).abs();
+                d.x + d.y <= radius
+            }
+        }
+    }
+
+    // Squared distance from `p` to the shape's edge; 0 when inside
+    pub fn dist_sq_to(&self, p: Vec2) -> f32 {
+        match *self {
+            Collider::Circle { center, radius } => {
+                let outside = (p.distance(center) - radius).max(0.0);
+                outside * outside
+            }
+            Collider::Diamond { center, radius } => {
+                // Rotate into the diamond's frame, where it is an axis-aligned
+                // square, then measure to that box
+                let d = p - center;
+                let u = (d.x + d.y) / std::f32::consts::SQRT_2;
+                let v = (d.x - d.y) / std::f32::consts::SQRT_2;
+                let half_side = radius / std::f32::consts::SQRT_2;
+                let du = (u.abs() - half_side).max(0.0);
+                let dv = (v.abs() - half_side).max(0.0);
+                du * du + dv * dv
+            }
+        }
+    }
+
+    // Outline for the collider debug view
+    pub fn draw_debug(&self, scale: f32, ox: f32, oy: f32, color: Color) {
+        let c = self.center();
+        let (cx, cy) = (ox + c.x * scale, oy + c.y * scale);
+        match *self {
+            Collider::Circle { radius, .. } => {
+                draw_circle_lines(cx, cy, radius * scale, 1.5, color);
+            }
+            Collider::Diamond { radius, .. } => {
+                let r = radius * scale;
+                let corners = [
+                    vec2(cx, cy - r),
+                    vec2(cx + r, cy),
+                    vec2(cx, cy + r),
+                    vec2(cx - r, cy),
+                ];
+                for i in 0..corners.len() {
+                    let a = corners[i];
+                    let b = corners[(i + 1) % corners.len()];
+                    draw_line(a.x, a.y, b.x, b.y, 1.5, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCLE: Collider = Collider::Circle {
+        center: vec2(100.0, 100.0),
+        radius: 10.0,
+    };
+    const DIAMOND: Collider = Collider::Diamond {
+        center: vec2(100.0, 100.0),
+        radius: 10.0,
+    };
+
+    #[test]
+    fn circle_contains_and_distance() {
+        assert!(CIRCLE.contains(vec2(100.0, 100.0)));
+        assert!(CIRCLE.contains(vec2(107.0, 107.0)));
+        assert!(!CIRCLE.contains(vec2(108.0, 108.0)));
+        assert_eq!(CIRCLE.dist_sq_to(vec2(105.0, 100.0)), 0.0);
+        assert!((CIRCLE.dist_sq_to(vec2(100.0, 130.0)) - 400.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn diamond_contains_and_distance() {
+        assert!(DIAMOND.contains(vec2(110.0, 100.0)));
+        assert!(DIAMOND.contains(vec2(105.0, 105.0)));
+        // Inside the bounding circle but past the diagonal edge
+        assert!(!DIAMOND.contains(vec2(107.0, 107.0)));
+        assert_eq!(DIAMOND.dist_sq_to(vec2(104.0, 104.0)), 0.0);
+        // Straight out past a corner
+        assert!((DIAMOND.dist_sq_to(vec2(130.0, 100.0)) - 400.0).abs() < 1e-3);
+        // Square to an edge: from (110, 110) to the edge midpoint (105, 105)
+        assert!((DIAMOND.dist_sq_to(vec2(110.0, 110.0)) - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn distance_is_zero_exactly_where_contained() {
+        for collider in [CIRCLE, DIAMOND] {
+            for x in (80..=120).step_by(4) {
+                for y in (80..=120).step_by(4) {
+                    let p = vec2(x as f32, y as f32);
+                    let dist_sq = collider.dist_sq_to(p);
+                    if collider.contains(p) {
+                        assert!(dist_sq < 1e-3, "{:?} at {}", collider, p);
+                    } else {
+                        assert!(dist_sq > 0.0, "{:?} at {}", collider, p);
+                    }
+                }
+            }
+        }
+    }
+}
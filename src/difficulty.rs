@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+// Built-in curves, compiled in so the game always has them
+const PRESET_EASY: &str = include_str!("../assets/difficulty/easy.toml");
+const PRESET_NORMAL: &str = include_str!("../assets/difficulty/normal.toml");
+const PRESET_HARD: &str = include_str!("../assets/difficulty/hard.toml");
+
+pub const PRESET_NAMES: [&str; 3] = ["easy", "normal", "hard"];
+
+// Multipliers at one point along the curve
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyKey {
+    pub progress: f32,
+    pub spawn_rate: f32,
+    pub speed: f32,
+    pub size: f32,
+    pub spawn_count: f32,
+}
+
+// What the spawner should do right now, sampled from a curve
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DifficultyLevel {
+    pub spawn_rate: f32,
+    pub speed: f32,
+    pub size: f32,
+    pub spawn_count: u32,
+}
+
+impl Default for DifficultyLevel {
+    fn default() -> Self {
+        Self {
+            spawn_rate: 1.0,
+            speed: 1.0,
+            size: 1.0,
+            spawn_count: 1,
+        }
+    }
+}
+
+// Piecewise linear difficulty over "progress", which grows with both
+// survival time and score so strong players ramp up faster.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyCurve {
+    pub name: String,
+    pub time_scale: f32,  // Seconds alive per unit of progress
+    pub score_scale: f32, // Score per unit of progress
+    pub keys: Vec<DifficultyKey>,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self::preset("normal").expect("normal preset exists")
+    }
+}
+
+impl DifficultyCurve {
+    pub fn preset(name: &str) -> Option<Self> {
+        let text = match name {
+            "easy" => PRESET_EASY,
+            "normal" => PRESET_NORMAL,
+            "hard" => PRESET_HARD,
+            _ => return None,
+        };
+        Some(Self::from_toml(text).expect("built-in difficulty presets are valid"))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let curve: DifficultyCurve = toml::from_str(text).map_err(|err| err.to_string())?;
+        curve.validate()?;
+        Ok(curve)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.time_scale <= 0.0 || self.score_scale <= 0.0 {
+            return Err("time_scale and score_scale must be positive".to_owned());
+        }
+        if self.keys.is_empty() {
+            return Err("a difficulty curve needs at least one key".to_owned());
+        }
+        if self
+            .keys
+            .windows(2)
+            .any(|pair| pair[1].progress < pair[0].progress)
+        {
+            return Err("difficulty keys must be sorted by progress".to_owned());
+        }
+        if self
+            .keys
+            .iter()
+            .any(|k| k.spawn_rate <= 0.0 || k.speed < 0.0 || k.size <= 0.0 || k.spawn_count < 1.0)
+        {
+            return Err(
+                "spawn_rate and size must be positive, speed non-negative, spawn_count at least 1"
+                    .to_owned(),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn progress(&self, time_alive: f32, score: u32) -> f32 {
+        time_alive / self.time_scale + score as f32 / self.score_scale
+    }
+
+    pub fn sample(&self, time_alive: f32, score: u32) -> DifficultyLevel {
+        let progress = self.progress(time_alive, score);
+
+        // Find the keys either side of `progress`, holding the ends
+        let next = self.keys.iter().position(|k| k.progress > progress);
+        let (a, b) = match next {
+            Some(0) => (self.keys[0], self.keys[0]),
+            Some(i) => (self.keys[i - 1], self.keys[i]),
+            None => {
+                let last = self.keys[self.keys.len() - 1];
+                (last, last)
+            }
+        };
+
+        let span = b.progress - a.progress;
+        let t = if span > 0.0 {
+            (progress - a.progress) / span
+        } else {
+            0.0
+        };
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        DifficultyLevel {
+            spawn_rate: lerp(a.spawn_rate, b.spawn_rate),
+            speed: lerp(a.speed, b.speed),
+            size: lerp(a.size, b.size),
+            spawn_count: lerp(a.spawn_count, b.spawn_count).floor().max(1.0) as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVE: &str = r#"
+name = "test"
+time_scale = 10.0
+score_scale = 20.0
+
+[[keys]]
+progress = 1.0
+spawn_rate = 1.0
+speed = 1.0
+size = 1.0
+spawn_count = 1.0
+
+[[keys]]
+progress = 3.0
+spawn_rate = 3.0
+speed = 2.0
+size = 0.5
+spawn_count = 3.0
+"#;
+
+    fn curve() -> DifficultyCurve {
+        DifficultyCurve::from_toml(CURVE).unwrap()
+    }
+
+    #[test]
+    fn progress_counts_time_and_score() {
+        assert_eq!(curve().progress(20.0, 20), 3.0);
+    }
+
+    #[test]
+    fn sample_holds_the_first_key_before_the_curve() {
+        let level = curve().sample(0.0, 0);
+        assert_eq!(
+            level,
+            DifficultyLevel {
+                spawn_rate: 1.0,
+                speed: 1.0,
+                size: 1.0,
+                spawn_count: 1,
+            }
+        );
+        assert_eq!(curve().sample(10.0, 0), level);
+    }
+
+    #[test]
+    fn sample_holds_the_last_key_after_the_curve() {
+        let last = DifficultyLevel {
+            spawn_rate: 3.0,
+            speed: 2.0,
+            size: 0.5,
+            spawn_count: 3,
+        };
+        assert_eq!(curve().sample(30.0, 0), last);
+        assert_eq!(curve().sample(1000.0, 500), last);
+    }
+
+    #[test]
+    fn sample_interpolates_between_keys() {
+        // Progress 2: halfway from the first key to the second
+        let level = curve().sample(10.0, 20);
+        assert_eq!(level.spawn_rate, 2.0);
+        assert_eq!(level.speed, 1.5);
+        assert_eq!(level.size, 0.75);
+        assert_eq!(level.spawn_count, 2);
+    }
+
+    #[test]
+    fn presets_load() {
+        for name in PRESET_NAMES {
+            assert_eq!(DifficultyCurve::preset(name).unwrap().name, name);
+        }
+        assert!(DifficultyCurve::preset("nightmare").is_none());
+    }
+
+    #[test]
+    fn rejects_broken_curves() {
+        let unsorted = CURVE.replace("progress = 3.0", "progress = 0.5");
+        assert!(DifficultyCurve::from_toml(&unsorted).is_err());
+        let no_scale = CURVE.replace("score_scale = 20.0", "score_scale = 0.0");
+        assert!(DifficultyCurve::from_toml(&no_scale).is_err());
+        let no_keys = format!("{}keys = []", &CURVE[..CURVE.find("[[keys]]").unwrap()]);
+        assert!(DifficultyCurve::from_toml(&no_keys).is_err());
+    }
+}
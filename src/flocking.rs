@@ -0,0 +1,77 @@
+use crate::popup::Popup;
+use crate::tuning::Tuning;
+use macroquad::prelude::*;
+
+// Neighbours further apart than this many personal-space radii are ignored
+// when matching headings
+const ALIGNMENT_REACH: f32 = 3.0;
+
+// Boids-style steering applied after every popup has moved this step.
+// Separation pushes overlapping popups apart so they ring the cursor instead
+// of stacking; alignment (off by default) nudges each popup's velocity
+// toward its neighbours' so groups drift together.
+pub fn spread_out(popups: &mut [Popup], tuning: &Tuning, dt: f32) {
+    if popups.len() < 2 || dt <= 0.0 {
+        return;
+    }
+    if tuning.separation_strength <= 0.0 && tuning.alignment <= 0.0 {
+        return;
+    }
+
+    let centers: Vec<Vec2> = popups
+        .iter()
+        .map(|p| vec2(p.x + p.w / 2.0, p.y + p.h / 2.0))
+        .collect();
+    let velocities: Vec<Vec2> = popups
+        .iter()
+        .map(|p| vec2(p.x - p.prev_x, p.y - p.prev_y) / dt)
+        .collect();
+    let radii: Vec<f32> = popups
+        .iter()
+        .map(|p| p.w.max(p.h) / 2.0 * tuning.separation_spacing)
+        .collect();
+
+    let mut offsets = vec![Vec2::ZERO; popups.len()];
+    for i in 0..popups.len() {
+        let mut push = Vec2::ZERO;
+        let mut heading = Vec2::ZERO;
+        let mut neighbours = 0;
+
+        for j in 0..popups.len() {
+            if i == j {
+                continue;
+            }
+            let delta = centers[i] - centers[j];
+            let dist = delta.length();
+            let personal_space = radii[i] + radii[j];
+
+            if dist < personal_space {
+                // Stronger the deeper the overlap; popups sitting exactly on
+                // top of each other split along an arbitrary but stable axis
+                let away = if dist > 0.01 {
+                    delta / dist
+                } else if i < j {
+                    Vec2::X
+                } else {
+                    -Vec2::X
+                };
+                push += away * (1.0 - dist / personal_space);
+            }
+            if dist < personal_space * ALIGNMENT_REACH {
+                heading += velocities[j];
+                neighbours += 1;
+            }
+        }
+
+        offsets[i] += push * tuning.separation_strength * dt;
+        if neighbours > 0 {
+            let average = heading / neighbours as f32;
+            offsets[i] += (average - velocities[i]) * tuning.alignment * dt;
+        }
+    }
+
+    for (popup, offset) in popups.iter_mut().zip(offsets) {
+        popup.x += offset.x;
+        popup.y += offset.y;
+    }
+}
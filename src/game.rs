@@ -1,16 +1,25 @@
 use crate::assets::GameAssets;
+use crate::behavior::BehaviorKind;
+use crate::boss::{self, Boss, MINIONS_PER_SUMMON};
+use crate::difficulty::DifficultyCurve;
+use crate::flocking;
+use crate::input::FrameInput;
+use crate::modes::GameMode;
+use crate::pickups::{
+    ActiveEffects, BIG_BLAST_FACTOR, HEALTH_RESTORE, Pickup, PickupKind, SLOW_TIME_FACTOR,
+};
 use crate::popup::Popup;
+use crate::rng::GameRng;
+use crate::scoring::{self, Combo};
+use crate::spawning::{self, Placement};
+use crate::tuning::Tuning;
 use crate::ui::UI;
-use macroquad::audio::{PlaySoundParams, play_sound};
+use crate::waves::{BANNER_TIME, WaveDirector, WaveScript};
 use macroquad::prelude::*;
 use macroquad_particles::{ColorCurve, Emitter, EmitterConfig};
+use serde::{Deserialize, Serialize};
 pub const VIRTUAL_W: f32 = 800.0;
 pub const VIRTUAL_H: f32 = 600.0;
-pub const SPAWN_INTERVAL: f32 = 1.2;
-pub const EXPLOSION_RADIUS: f32 = 90.0;
-pub const ENERGY_COST: f32 = 0.2;
-pub const ENERGY_REGEN: f32 = 0.1;
-pub const DAMAGE_RATE: f32 = 0.15;
 
 // Draw a teardrop shape pointing in a direction
 fn draw_teardrop(x: f32, y: f32, size: f32, direction: f32, color: Color) {
@@ -32,12 +41,98 @@ fn draw_teardrop(x: f32, y: f32, size: f32, direction: f32, color: Color) {
     draw_triangle_lines(tip, back_left, back_right, 1.5, color);
 }
 
+// Explosion rings are stored in virtual space and scaled when drawn
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExplosionCircle {
     pub x: f32,
     pub y: f32,
     pub radius: f32,
     pub timer: f32,
 }
+
+// A popup announced by a spawn warning, entering play once `timer` reaches
+// `Tuning::telegraph_time`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IncomingSpawn {
+    pub popup: Popup,
+    pub timer: f32,
+}
+
+// A secondary explosion waiting to go off where a popup was destroyed.
+// `depth` counts links from the player's explosion (1 = first chain link).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChainBlast {
+    pub x: f32,
+    pub y: f32,
+    pub delay: f32,
+    pub depth: u32,
+}
+
+// What set off a blast, which decides how its kills score and whether they
+// chain any further
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlastSource {
+    Player,     // Explosion or zap: multi-kill points, extends the combo
+    Chain(u32), // Chain link this many steps from the player's explosion
+    Shockwave,  // Boss death: a point per kill, no chains, combo untouched
+}
+
+// Score callouts that drift up from where they were earned, in virtual space
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FloatingText {
+    pub x: f32,
+    pub y: f32,
+    pub text: String,
+    pub timer: f32,
+}
+
+const FLOATING_TEXT_TIME: f32 = 1.0;
+const FLOATING_TEXT_RISE: f32 = 40.0; // Virtual pixels per second
+
+// Per-run counters, reset on restart. Used by the headless simulator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunStats {
+    pub time_alive: f32,
+    pub explosions_used: u32,
+    pub zaps_used: u32,
+    pub kills: u32,
+    pub best_multiplier: u32,
+    pub longest_chain: u32,
+    pub bosses_defeated: u32,
+    pub energy_starved_time: f32, // Time spent with too little energy to explode
+}
+
+// Top-level screens. Each scene has its own update/draw pair on GameState;
+// a scene's update returns the scene to switch to, if any.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scene {
+    Title,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+// Sounds requested by the simulation, played by `GameState::flush_effects`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundCue {
+    Start,
+    Click,
+    Zap,
+    GameOver,
+    Pickup,
+    BossDeath,
+    Telegraph,
+}
+
+// Full damage at the centre of a blast, tapering towards the edge. A blast
+// with no radius only reaches what it is inside of and deals full damage.
+fn blast_damage(full_damage: f32, falloff: f32, dist_sq: f32, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return full_damage;
+    }
+    full_damage * (1.0 - falloff * dist_sq.sqrt() / radius)
+}
+
 // ---------------- PARTICLE CONFIG ----------------
 
 fn particle_explosion() -> EmitterConfig {
@@ -55,42 +150,96 @@ fn particle_explosion() -> EmitterConfig {
     }
 }
 
+// Scale and letterbox offset that map the virtual area onto the window
+pub fn get_scaling() -> (f32, f32, f32) {
+    let scale_x = screen_width() / VIRTUAL_W;
+    let scale_y = screen_height() / VIRTUAL_H;
+    let scale = scale_x.min(scale_y);
+
+    let offset_x = (screen_width() - VIRTUAL_W * scale) / 2.0;
+    let offset_y = (screen_height() - VIRTUAL_H * scale) / 2.0;
+    (scale, offset_x, offset_y)
+}
+
+// Serializable for save games; effects waiting to be presented are not saved
+#[derive(Serialize, Deserialize)]
 pub struct GameState {
+    pub scene: Scene,
     pub popups: Vec<Popup>,
+    pub incoming: Vec<IncomingSpawn>,
+    pub boss: Option<Boss>,
+    pub rng: GameRng, // Drives every random gameplay decision; same seed = same run
+    pub tuning: Tuning,
+    pub difficulty: DifficultyCurve, // Resolved from `tuning.difficulty`
     pub explosions: Vec<ExplosionCircle>,
-    pub emitters: Vec<(Emitter, Vec2)>,
-    pub spawn_timer: f32,
+    pub chain_blasts: Vec<ChainBlast>,
+    pub floating_texts: Vec<FloatingText>,
+    pub pickups: Vec<Pickup>,
+    pub effects: ActiveEffects,
+    #[serde(skip)]
+    pub(crate) emitters: Vec<(Emitter, Vec2)>,
+    #[serde(skip)]
+    pub(crate) frozen_particles: Option<Texture2D>, // Still of the world shown while paused
+    #[serde(skip)]
+    pub(crate) pending_bursts: Vec<Vec2>, // Particle bursts waiting for an emitter (virtual space)
+    #[serde(skip)]
+    pub(crate) pending_sounds: Vec<SoundCue>,
+    #[serde(skip)]
+    pub debug_colliders: bool, // Draw hit shapes over everything
+    pub mode: GameMode,
+    pub wave_script: WaveScript,
+    pub waves: WaveDirector,
     pub score: u32,
+    pub combo: Combo,
+    pub stats: RunStats,
     pub health: f32,
     pub energy: f32,
-    pub game_over_sound_played: bool,
-    pub start_sound_played: bool,
-    pub frames_since_start: u32,
-    pub cursor_trail: Vec<(f32, f32, f32)>, // (x, y, age)
-    pub pulse_timer: f32,
+    pub charge: Option<f32>, // Seconds right-click has been held, while charging
+    pub(crate) cursor_trail: Vec<(f32, f32, f32)>, // (x, y, age) in virtual space
+    pub(crate) pulse_timer: f32,
     pub last_mouse_x: f32,
     pub last_mouse_y: f32,
-    pub arrow_alpha: f32,
-    pub movement_direction: f32, // Direction in radians
+    pub cursor_vx: f32, // Smoothed cursor velocity, read by flanking popups
+    pub cursor_vy: f32,
+    pub(crate) arrow_alpha: f32,
+    pub(crate) movement_direction: f32, // Direction in radians
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(seed: u64, tuning: Tuning) -> Self {
         Self {
+            scene: Scene::Title,
             popups: Vec::new(),
+            incoming: Vec::new(),
+            boss: None,
+            rng: GameRng::new(seed),
+            difficulty: DifficultyCurve::preset(&tuning.difficulty).unwrap_or_default(),
+            tuning,
             explosions: Vec::new(),
+            chain_blasts: Vec::new(),
+            floating_texts: Vec::new(),
+            pickups: Vec::new(),
+            effects: ActiveEffects::default(),
             emitters: Vec::new(),
-            spawn_timer: 0.0,
+            frozen_particles: None,
+            pending_bursts: Vec::new(),
+            pending_sounds: Vec::new(),
+            debug_colliders: false,
+            mode: GameMode::default(),
+            wave_script: WaveScript::default(),
+            waves: WaveDirector::new(),
             score: 0,
+            combo: Combo::default(),
+            stats: RunStats::default(),
             health: 1.0,
             energy: 1.0,
-            game_over_sound_played: false,
-            start_sound_played: false,
-            frames_since_start: 0,
+            charge: None,
             cursor_trail: Vec::new(),
             pulse_timer: 0.0,
             last_mouse_x: 0.0,
             last_mouse_y: 0.0,
+            cursor_vx: 0.0,
+            cursor_vy: 0.0,
             arrow_alpha: 1.0,
             movement_direction: 0.0,
         }
@@ -99,61 +248,167 @@ impl GameState {
     pub fn reset(&mut self) {
         self.health = 1.0;
         self.energy = 1.0;
+        self.charge = None;
         self.score = 0;
-        self.game_over_sound_played = false;
+        self.combo = Combo::default();
+        self.stats = RunStats::default();
+        self.waves = WaveDirector::new();
         self.popups.clear();
+        self.incoming.clear();
+        self.boss = None;
         self.explosions.clear();
+        self.chain_blasts.clear();
+        self.floating_texts.clear();
+        self.pickups.clear();
+        self.effects = ActiveEffects::default();
         self.emitters.clear();
+        self.pending_bursts.clear();
         self.cursor_trail.clear();
         self.arrow_alpha = 1.0;
     }
 
-    pub fn update(&mut self, dt: f32, assets: &GameAssets) {
-        self.frames_since_start += 1;
+    // Swap in new tuning values, e.g. after a hot reload
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        self.difficulty = DifficultyCurve::preset(&tuning.difficulty).unwrap_or_default();
+        self.tuning = tuning;
+    }
 
-        if !self.start_sound_played && self.frames_since_start > 10 {
-            play_sound(
-                &assets.snd_start,
-                PlaySoundParams {
-                    looped: false,
-                    volume: 1.0,
-                },
-            );
-            self.start_sound_played = true;
+    // Current blast reach, including the big-blast power-up
+    pub fn explosion_radius(&self) -> f32 {
+        if self.effects.big_blast > 0.0 {
+            self.tuning.explosion_radius * BIG_BLAST_FACTOR
+        } else {
+            self.tuning.explosion_radius
         }
+    }
 
-        if self.health <= 0.0 {
-            if is_key_pressed(KeyCode::R) {
+    // How far the current charge has got, 0..1 (0 when not charging)
+    pub fn charge_level(&self) -> f32 {
+        self.charge_level_at(self.charge.unwrap_or(0.0))
+    }
+
+    fn charge_level_at(&self, held: f32) -> f32 {
+        (held / self.tuning.charge_time).min(1.0)
+    }
+
+    // Blast reach and energy cost of an explosion charged to `level`
+    pub fn charged_radius(&self, level: f32) -> f32 {
+        self.explosion_radius() * (1.0 + (self.tuning.charge_radius_scale - 1.0) * level)
+    }
+
+    pub fn charged_cost(&self, level: f32) -> f32 {
+        self.tuning.energy_cost * (1.0 + (self.tuning.charge_cost_scale - 1.0) * level)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.scene == Scene::GameOver
+    }
+
+    // A run is underway, possibly paused
+    pub fn in_run(&self) -> bool {
+        matches!(self.scene, Scene::Playing | Scene::Paused)
+    }
+
+    // Skip the title screen and begin a fresh run (used by headless tools)
+    pub fn start_run(&mut self) {
+        self.switch_scene(Scene::Playing);
+    }
+
+    pub fn update(&mut self, dt: f32, input: &FrameInput) {
+        self.update_cursor(dt, input);
+
+        let next = match self.scene {
+            Scene::Title => self.update_title(input),
+            Scene::Playing => self.update_playing(dt, input),
+            Scene::Paused => self.update_paused(input),
+            Scene::GameOver => self.update_game_over(input),
+        };
+
+        if let Some(next) = next {
+            self.switch_scene(next);
+        }
+    }
+
+    fn switch_scene(&mut self, next: Scene) {
+        match (self.scene, next) {
+            // Resuming keeps the run as it was
+            (Scene::Paused, Scene::Playing) => {}
+            (_, Scene::Playing) => {
                 self.reset();
-                play_sound(
-                    &assets.snd_start,
-                    PlaySoundParams {
-                        looped: false,
-                        volume: 1.0,
-                    },
-                );
+                self.pending_sounds.push(SoundCue::Start);
             }
-            return;
+            (_, Scene::GameOver) => self.pending_sounds.push(SoundCue::GameOver),
+            _ => {}
         }
+        self.scene = next;
+    }
 
-        // Energy regeneration
-        self.energy = (self.energy + ENERGY_REGEN * dt).min(1.0);
+    fn update_title(&mut self, input: &FrameInput) -> Option<Scene> {
+        if input.menu_up {
+            self.mode = self.mode.step(-1);
+        }
+        if input.menu_down {
+            self.mode = self.mode.step(1);
+        }
+        if input.confirm || input.explode || input.zap {
+            return Some(Scene::Playing);
+        }
+        None
+    }
+
+    // R abandons the run for the title screen; the only way out of zen mode
+    fn update_paused(&mut self, input: &FrameInput) -> Option<Scene> {
+        if input.pause {
+            return Some(Scene::Playing);
+        }
+        if input.restart {
+            return Some(Scene::Title);
+        }
+        None
+    }
+
+    fn update_game_over(&mut self, input: &FrameInput) -> Option<Scene> {
+        if input.restart {
+            return Some(Scene::Playing);
+        }
+        if input.pause {
+            return Some(Scene::Title);
+        }
+        None
+    }
+
+    // Seconds left on the clock in timed modes
+    pub fn time_left(&self) -> Option<f32> {
+        self.mode
+            .time_limit()
+            .map(|limit| (limit - self.stats.time_alive).max(0.0))
+    }
 
+    // Cursor trail and arrow, tracked in every scene so the cursor stays alive on menus
+    fn update_cursor(&mut self, dt: f32, input: &FrameInput) {
         // Update pulse timer for cursor glow
         self.pulse_timer += dt;
 
+        let mx = input.cursor_x;
+        let my = input.cursor_y;
+
         // Update cursor trail
-        let (raw_mx, raw_my) = mouse_position();
-        self.cursor_trail.push((raw_mx, raw_my, 0.0));
+        self.cursor_trail.push((mx, my, 0.0));
         for trail_point in self.cursor_trail.iter_mut() {
             trail_point.2 += dt;
         }
         self.cursor_trail.retain(|p| p.2 < 0.3); // Keep trail for 0.3 seconds
 
         // Track mouse movement for arrow visibility and direction
-        let dx = raw_mx - self.last_mouse_x;
-        let dy = raw_my - self.last_mouse_y;
+        let dx = mx - self.last_mouse_x;
+        let dy = my - self.last_mouse_y;
         let movement_speed = (dx * dx + dy * dy).sqrt();
+
+        if dt > 0.0 {
+            let blend = (dt * 10.0).min(1.0);
+            self.cursor_vx += (dx / dt - self.cursor_vx) * blend;
+            self.cursor_vy += (dy / dt - self.cursor_vy) * blend;
+        }
         
         // Calculate movement direction (angle in radians)
         if movement_speed > 0.1 {
@@ -171,148 +426,691 @@ impl GameState {
         self.arrow_alpha = self.arrow_alpha + (target_alpha - self.arrow_alpha) * (dt * 3.0).min(1.0);
         
         // Update last mouse position
-        self.last_mouse_x = raw_mx;
-        self.last_mouse_y = raw_my;
+        self.last_mouse_x = mx;
+        self.last_mouse_y = my;
+    }
+
+    fn update_playing(&mut self, dt: f32, input: &FrameInput) -> Option<Scene> {
+        if input.pause || input.focus_lost {
+            // Pausing drops a charge in progress; nothing was spent on it yet
+            self.charge = None;
+            return Some(Scene::Paused);
+        }
+
+        // Energy regeneration
+        self.energy = (self.energy + self.tuning.energy_regen * dt).min(1.0);
+        self.effects.update(dt);
+
+        self.stats.time_alive += dt;
+        if self.time_left() == Some(0.0) {
+            return Some(Scene::GameOver);
+        }
+        if self.energy < self.tuning.energy_cost {
+            self.stats.energy_starved_time += dt;
+        }
+
+        let mx = input.cursor_x;
+        let my = input.cursor_y;
 
-        // Get scaling
-        let (scale, offset_x, offset_y) = self.get_scaling();
-        let mx = (raw_mx - offset_x) / scale;
-        let my = (raw_my - offset_y) / scale;
+        // Spawn system: the wave director decides what appears, the
+        // difficulty curve scales how fast and how tough
+        let level = self.difficulty.sample(self.stats.time_alive, self.score);
+        let requests = self.waves.update(
+            dt,
+            &self.wave_script,
+            &self.tuning,
+            &level,
+            self.popups.len() + self.incoming.len(),
+            self.boss.is_some(),
+            &mut self.rng,
+        );
+        let requests_empty = requests.is_empty();
+        for request in requests {
+            let kind = request
+                .behavior
+                .unwrap_or_else(|| BehaviorKind::pick(request.shape, &mut self.rng));
+            let mut popup = Popup::new(
+                VIRTUAL_W,
+                VIRTUAL_H,
+                kind,
+                &self.tuning,
+                &level,
+                &mut self.rng,
+            );
+            spawning::place(
+                &mut popup,
+                request.placement,
+                vec2(mx, my),
+                self.tuning.spawn_safe_distance,
+                &mut self.rng,
+            );
+            self.incoming.push(IncomingSpawn { popup, timer: 0.0 });
+        }
+        if !requests_empty {
+            self.pending_sounds.push(SoundCue::Telegraph);
+        }
 
-        // Spawn system
-        self.spawn_timer += dt;
-        if self.spawn_timer >= SPAWN_INTERVAL {
-            self.popups
-                .push(Popup::new(VIRTUAL_W, VIRTUAL_H, assets.char_bodies.len()));
-            self.spawn_timer = 0.0;
+        // Warnings that have run their course turn into real popups
+        for spawn in self.incoming.iter_mut() {
+            spawn.timer += dt;
+        }
+        let telegraph_time = self.tuning.telegraph_time;
+        let (ready, waiting): (Vec<_>, Vec<_>) = self
+            .incoming
+            .drain(..)
+            .partition(|s| s.timer >= telegraph_time);
+        self.incoming = waiting;
+        self.popups.extend(ready.into_iter().map(|s| s.popup));
+        if self.waves.take_boss() {
+            self.spawn_boss(mx, my);
         }
 
-        // Update popups & Health drain
+        // Update popups & Health drain. Slow-time only slows the popups, the
+        // cursor and timers run at full speed; the shield blocks the drain.
+        let popup_dt = if self.effects.slow_time > 0.0 {
+            dt * SLOW_TIME_FACTOR
+        } else {
+            dt
+        };
+        let shielded = self.effects.shield > 0.0;
+        let pulse_aware = self.tuning.pulse_hitboxes;
+        let mut drain = 0.0;
         for popup in self.popups.iter_mut() {
-            popup.update(dt);
-            popup.follow(mx, my, dt);
-            if popup.hit(mx, my) {
-                self.health -= DAMAGE_RATE * dt;
+            popup.update(popup_dt);
+            popup.follow(mx, my, self.cursor_vx, self.cursor_vy, popup_dt);
+            if popup.hit(mx, my, pulse_aware) && !shielded {
+                drain += self.tuning.damage_rate * dt;
             }
         }
-        self.health = self.health.max(0.0);
+        flocking::spread_out(&mut self.popups, &self.tuning, popup_dt);
 
-        // Explosion logic
-        let mut exploded = is_mouse_button_pressed(MouseButton::Right);
-        if exploded && self.energy < ENERGY_COST {
-            exploded = false;
+        if let Some(boss) = self.boss.as_mut() {
+            let summons = boss.update(popup_dt, vec2(mx, my));
+            if boss.hit(mx, my) && !shielded {
+                drain += self.tuning.damage_rate * boss::CONTACT_DAMAGE * dt;
+            }
+            let (bx, by, reach) = (boss.x, boss.y, boss.radius());
+            for i in 0..summons {
+                // Minions appear on opposite sides of the boss, but never
+                // on top of the cursor
+                let angle = self.rng.gen_range_f32(0.0, std::f32::consts::PI)
+                    + i as f32 * std::f32::consts::TAU / MINIONS_PER_SUMMON as f32;
+                let mut minion = Popup::new(
+                    VIRTUAL_W,
+                    VIRTUAL_H,
+                    BehaviorKind::Chaser,
+                    &self.tuning,
+                    &level,
+                    &mut self.rng,
+                );
+                spawning::place(
+                    &mut minion,
+                    Placement::Near(bx + angle.cos() * reach, by + angle.sin() * reach),
+                    vec2(mx, my),
+                    self.tuning.spawn_safe_distance,
+                    &mut self.rng,
+                );
+                // Minions are announced like any other spawn
+                self.incoming.push(IncomingSpawn {
+                    popup: minion,
+                    timer: 0.0,
+                });
+            }
+            if summons > 0 {
+                self.pending_sounds.push(SoundCue::Telegraph);
+            }
+        }
+
+        // The mode decides what contact actually costs
+        let damage = self.mode.contact_damage(drain);
+        self.health -= damage;
+        let took_damage = damage > 0.0;
+        // Checked before pickups so a health pickup cannot undo a fatal hit
+        if self.health <= 0.0 {
+            self.health = 0.0;
+            return Some(Scene::GameOver);
+        }
+
+        self.update_pickups(dt, mx, my);
+
+        // Explosion logic: holding right-click charges, releasing detonates.
+        // A quick click is a plain uncharged explosion. The charge only grows
+        // while there is energy to pay for the bigger blast.
+        if input.charge && self.charge.is_none() && self.energy >= self.tuning.energy_cost {
+            self.charge = Some(0.0);
+        }
+        if let Some(held) = self.charge {
+            let next = held + dt;
+            if self.charged_cost(self.charge_level_at(next)) <= self.energy {
+                self.charge = Some(next);
+            }
+        }
+        let mut blast = None;
+        if input.explode || (self.charge.is_some() && !input.charge) {
+            let level = self.charge_level();
+            let cost = self.charged_cost(level);
+            if self.energy >= cost {
+                blast = Some((self.charged_radius(level), cost));
+            }
+            self.charge = None;
         }
-        let mut explosion_requests: Vec<Vec2> = Vec::new();
 
         self.explosions.retain_mut(|e| {
             e.timer += dt;
             e.timer < 0.2
         });
+        self.floating_texts.retain_mut(|t| {
+            t.timer += dt;
+            t.y -= FLOATING_TEXT_RISE * dt;
+            t.timer < FLOATING_TEXT_TIME
+        });
+
+        // Getting hit drops the multiplier, otherwise it bleeds off over time
+        if took_damage {
+            self.combo.break_combo();
+        } else {
+            self.combo.update(dt);
+        }
 
         // Emitters will be updated and filtered in draw phase
 
-        if exploded {
-            self.energy -= ENERGY_COST;
-            let current_score = &mut self.score;
-            self.popups.retain(|p: &Popup| {
-                let closest_x = mx.clamp(p.x, p.x + p.w);
-                let closest_y = my.clamp(p.y, p.y + p.h);
-                let dx = mx - closest_x;
-                let dy = my - closest_y;
-                let dist_sq = dx * dx + dy * dy;
-
-                if dist_sq <= EXPLOSION_RADIUS * EXPLOSION_RADIUS {
-                    *current_score += 1;
-                    explosion_requests.push(vec2(p.x + p.w / 2.0, p.y + p.h / 2.0));
-
-                    false
-                } else {
-                    true
-                }
-            });
+        // Zap: cheap single-target attack on the popup under the cursor. A
+        // miss still costs energy.
+        if input.zap && self.energy >= self.tuning.zap_cost {
+            self.energy -= self.tuning.zap_cost;
+            self.stats.zaps_used += 1;
+            // Topmost popup, i.e. the last one drawn
+            let pulse_aware = self.tuning.pulse_hitboxes;
+            if let Some(index) = self.popups.iter().rposition(|p| p.hit(mx, my, pulse_aware)) {
+                let p = self.popups.remove(index);
+                let center = vec2(p.x + p.w / 2.0, p.y + p.h / 2.0);
+                self.explosions.push(ExplosionCircle {
+                    x: center.x,
+                    y: center.y,
+                    radius: p.w.max(p.h) / 2.0,
+                    timer: 0.0,
+                });
+                self.score_kills(vec![center], mx, my, BlastSource::Player);
+            }
+            self.pending_sounds.push(SoundCue::Zap);
+        }
 
-            play_sound(
-                &assets.snd_click,
-                PlaySoundParams {
-                    looped: false,
-                    volume: 1.0,
-                },
+        if let Some((radius, cost)) = blast {
+            self.energy -= cost;
+            self.stats.explosions_used += 1;
+            self.detonate(
+                mx,
+                my,
+                radius,
+                self.tuning.explosion_damage,
+                BlastSource::Player,
             );
+        }
 
-            self.explosions.push(ExplosionCircle {
-                x: raw_mx,
-                y: raw_my,
-                radius: EXPLOSION_RADIUS * scale,
+        // Secondary explosions from popups destroyed by earlier blasts
+        for blast in self.chain_blasts.iter_mut() {
+            blast.delay -= dt;
+        }
+        let (due, waiting): (Vec<_>, Vec<_>) =
+            self.chain_blasts.drain(..).partition(|b| b.delay <= 0.0);
+        self.chain_blasts = waiting;
+        for blast in due {
+            self.detonate(
+                blast.x,
+                blast.y,
+                self.tuning.chain_radius,
+                self.tuning.chain_damage,
+                BlastSource::Chain(blast.depth),
+            );
+        }
+
+        if self.health <= 0.0 {
+            return Some(Scene::GameOver);
+        }
+        None
+    }
+
+    // Turn the audio/particle requests queued by `update` into real sounds and
+    // emitters. Kept separate so the simulation can run without a window.
+    pub fn flush_effects(&mut self, assets: &GameAssets) {
+        for cue in self.pending_sounds.drain(..) {
+            assets.play(cue);
+        }
+
+        let (scale, offset_x, offset_y) = get_scaling();
+        for pos in self.pending_bursts.drain(..) {
+            let screen_pos = vec2(offset_x + pos.x * scale, offset_y + pos.y * scale);
+            let mut config = particle_explosion();
+            config.colors_curve = ColorCurve {
+                start: Color::new(1.0, 0.647, 0.0, 1.0),
+                mid: Color::new(1.0, 0.4, 0.0, 0.8),
+                end: Color::new(0.5, 0.0, 0.0, 0.0),
+            };
+            let mut emitter = Emitter::new(config);
+            emitter.emit(screen_pos, 25);
+            self.emitters.push((emitter, screen_pos));
+        }
+    }
+
+    // Drop queued sounds and particle bursts when nothing is going to present them
+    pub fn discard_effects(&mut self) {
+        self.pending_sounds.clear();
+        self.pending_bursts.clear();
+    }
+
+    // `alpha` is the fixed-timestep interpolation factor between the previous
+    // and current simulation step.
+    pub fn draw(&mut self, assets: &GameAssets, alpha: f32) {
+        let (scale, offset_x, offset_y) = get_scaling();
+
+        clear_background(BLACK);
+
+        match self.scene {
+            Scene::Title => UI::draw_title(self.mode, scale, offset_x, offset_y, assets),
+            Scene::Playing => self.draw_world(scale, offset_x, offset_y, alpha, assets),
+            Scene::Paused => {
+                // Nothing moves while paused or after game over, so both
+                // interpolate to the last step
+                self.draw_world(scale, offset_x, offset_y, 1.0, assets);
+                UI::draw_paused(scale, offset_x, offset_y, assets);
+            }
+            Scene::GameOver => {
+                self.draw_world(scale, offset_x, offset_y, 1.0, assets);
+                // Running out the clock is how a time attack is meant to end
+                let (headline, color) = if self.time_left() == Some(0.0) {
+                    ("TIME UP", YELLOW)
+                } else {
+                    ("GAME OVER", RED)
+                };
+                UI::draw_game_over(headline, color, scale, offset_x, offset_y, assets);
+            }
+        }
+
+        self.draw_cursor(scale, offset_x, offset_y);
+    }
+
+    // Damage every popup in reach of an explosion at (x, y). Kills from the
+    // player's blast or a chain link queue the next link until
+    // `chain_max_depth` is reached; the boss shockwave never chains.
+    fn detonate(&mut self, x: f32, y: f32, radius: f32, full_damage: f32, source: BlastSource) {
+        let falloff = self.tuning.damage_falloff;
+        let pulse_aware = self.tuning.pulse_hitboxes;
+        let mut kill_positions: Vec<Vec2> = Vec::new();
+        self.popups.retain_mut(|p: &mut Popup| {
+            let dist_sq = p.dist_sq_to(x, y, pulse_aware);
+            if dist_sq > radius * radius {
+                return true;
+            }
+            let damage = blast_damage(full_damage, falloff, dist_sq, radius);
+            if p.take_damage(damage) {
+                kill_positions.push(vec2(p.x + p.w / 2.0, p.y + p.h / 2.0));
+                false
+            } else {
+                true
+            }
+        });
+
+        let mut boss_killed = false;
+        if let Some(boss) = self.boss.as_mut() {
+            let dist_sq = boss.dist_sq_to(x, y);
+            if dist_sq <= radius * radius {
+                let damage = blast_damage(full_damage, falloff, dist_sq, radius);
+                boss_killed = boss.take_damage(damage);
+            }
+        }
+
+        let depth = match source {
+            BlastSource::Player => Some(0),
+            BlastSource::Chain(depth) => Some(depth),
+            BlastSource::Shockwave => None,
+        };
+        if let Some(depth) = depth.filter(|d| *d < self.tuning.chain_max_depth) {
+            for pos in kill_positions.iter() {
+                self.chain_blasts.push(ChainBlast {
+                    x: pos.x,
+                    y: pos.y,
+                    delay: self.tuning.chain_delay,
+                    depth: depth + 1,
+                });
+            }
+        }
+
+        self.score_kills(kill_positions, x, y, source);
+
+        self.pending_sounds.push(SoundCue::Click);
+
+        self.explosions.push(ExplosionCircle {
+            x,
+            y,
+            radius,
+            timer: 0.0,
+        });
+
+        if boss_killed {
+            self.defeat_boss();
+        }
+    }
+
+    // Bring in this wave's boss on the far side of the arena from the cursor.
+    // Each boss beaten makes the next one sturdier.
+    fn spawn_boss(&mut self, cursor_x: f32, cursor_y: f32) {
+        let margin = boss::BOSS_SIZE;
+        let x = (VIRTUAL_W - cursor_x).clamp(margin, VIRTUAL_W - margin);
+        let y = if cursor_y > VIRTUAL_H / 2.0 {
+            margin
+        } else {
+            VIRTUAL_H - margin
+        };
+        let max_hp = self.tuning.boss_hp * (1.0 + 0.5 * self.stats.bosses_defeated as f32);
+        self.boss = Some(Boss::new(x, y, max_hp));
+    }
+
+    // Boss death: a ring of bursts and a shockwave that flattens everything
+    // nearby, on top of the boss bonus
+    fn defeat_boss(&mut self) {
+        let Some(boss) = self.boss.take() else {
+            return;
+        };
+        self.stats.bosses_defeated += 1;
+        self.stats.kills += 1;
+
+        let points = scoring::BOSS_POINTS * self.combo.multiplier();
+        self.score += points;
+        self.floating_texts.push(FloatingText {
+            x: boss.x,
+            y: boss.y - boss.radius(),
+            text: format!("BOSS DOWN +{}", points),
+            timer: 0.0,
+        });
+
+        const DEATH_BURSTS: usize = 16;
+        for i in 0..DEATH_BURSTS {
+            let angle = i as f32 / DEATH_BURSTS as f32 * std::f32::consts::TAU;
+            let reach = boss.radius() * if i % 2 == 0 { 1.0 } else { 0.5 };
+            self.pending_bursts.push(vec2(
+                boss.x + angle.cos() * reach,
+                boss.y + angle.sin() * reach,
+            ));
+        }
+        self.pending_sounds.push(SoundCue::BossDeath);
+
+        self.detonate(
+            boss.x,
+            boss.y,
+            boss::SHOCKWAVE_RADIUS,
+            boss::SHOCKWAVE_DAMAGE,
+            BlastSource::Shockwave,
+        );
+    }
+
+    // Score one attack's kills (given by their centres), announce it at
+    // (x, y), roll for drops and queue the particle bursts. Direct attacks
+    // feed the combo; chain links score a bonus at the current multiplier
+    // without extending it.
+    fn score_kills(&mut self, kill_positions: Vec<Vec2>, x: f32, y: f32, source: BlastSource) {
+        let kills = kill_positions.len() as u32;
+        let multiplier = self.combo.multiplier();
+        let points = match source {
+            BlastSource::Player => self.combo.register_blast(kills),
+            BlastSource::Chain(depth) => scoring::chain_points(kills, depth) * multiplier,
+            BlastSource::Shockwave => kills * multiplier,
+        };
+        self.score += points;
+        self.stats.kills += kills;
+        self.stats.best_multiplier = self.stats.best_multiplier.max(multiplier);
+        if let BlastSource::Chain(depth) = source
+            && kills > 0
+        {
+            self.stats.longest_chain = self.stats.longest_chain.max(depth);
+        }
+        if points > 0 {
+            let mut text = format!("+{}", points);
+            if let BlastSource::Chain(depth) = source {
+                text = format!("CHAIN {} {}", depth, text);
+            } else if source == BlastSource::Player && kills > 1 {
+                text = format!("{} KILLS {}", kills, text);
+            }
+            if multiplier > 1 {
+                text = format!("{} x{}", text, multiplier);
+            }
+            self.floating_texts.push(FloatingText {
+                x,
+                y: y - 20.0,
+                text,
                 timer: 0.0,
             });
+        }
 
-            // Spawn particle emitters at explosion request positions
-            for pos in explosion_requests.iter() {
-                let mut config = particle_explosion();
-                config.colors_curve = ColorCurve {
-                    start: Color::new(1.0, 0.647, 0.0, 1.0),
-                    mid: Color::new(1.0, 0.4, 0.0, 0.8),
-                    end: Color::new(0.5, 0.0, 0.0, 0.0),
-                };
-                let mut emitter = Emitter::new(config);
-                emitter.emit(*pos, 25);
-                self.emitters.push((emitter, *pos));
+        // Some of the destroyed popups leave a power-up behind
+        for pos in kill_positions.iter() {
+            if self.rng.next_f32() < self.tuning.pickup_drop_chance {
+                let kind = PickupKind::random(self.mode.drops(), &mut self.rng);
+                self.pickups.push(Pickup::new(pos.x, pos.y, kind));
             }
         }
 
-        if self.health <= 0.0 && !self.game_over_sound_played {
-            play_sound(
-                &assets.snd_over,
-                PlaySoundParams {
-                    looped: false,
-                    volume: 1.0,
-                },
-            );
-            self.game_over_sound_played = true;
+        // Particle emitters need a GL context, so they are created in flush_effects
+        self.pending_bursts.extend(kill_positions);
+    }
+
+    // Age pickups out and collect any the cursor is touching
+    fn update_pickups(&mut self, dt: f32, mx: f32, my: f32) {
+        let mut collected = Vec::new();
+        self.pickups.retain_mut(|p| {
+            if p.touches(mx, my) {
+                collected.push(p.clone());
+                return false;
+            }
+            p.update(dt)
+        });
+
+        for pickup in collected {
+            match pickup.kind {
+                PickupKind::Energy => self.energy = 1.0,
+                PickupKind::Health => self.health = (self.health + HEALTH_RESTORE).min(1.0),
+                kind => self.effects.activate(kind),
+            }
+            self.floating_texts.push(FloatingText {
+                x: pickup.x,
+                y: pickup.y - 20.0,
+                text: pickup.kind.label().to_owned(),
+                timer: 0.0,
+            });
+            self.pending_sounds.push(SoundCue::Pickup);
         }
     }
 
-    pub fn draw(&mut self, assets: &GameAssets) {
-        let (scale, offset_x, offset_y) = self.get_scaling();
-        let (raw_mx, raw_my) = mouse_position();
+    // Popups, explosions, particles and the HUD
+    fn draw_world(
+        &mut self,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        alpha: f32,
+        assets: &GameAssets,
+    ) {
+        // Draw pickups under the popups
+        for pickup in self.pickups.iter() {
+            pickup.draw_scaled(scale, offset_x, offset_y, &assets.font);
+        }
 
-        clear_background(BLACK);
+        // Draw spawn warnings
+        let telegraph_time = self.tuning.telegraph_time.max(f32::EPSILON);
+        for spawn in self.incoming.iter() {
+            spawn.popup.draw_telegraph(
+                scale,
+                offset_x,
+                offset_y,
+                (spawn.timer / telegraph_time).min(1.0),
+                &assets.char_bodies,
+            );
+        }
 
         // Draw popups
         for popup in self.popups.iter() {
-            popup.draw_scaled(scale, offset_x, offset_y, &assets.char_bodies);
+            let show_hp = popup.is_tough(self.tuning.explosion_damage);
+            popup.draw_scaled(
+                scale,
+                offset_x,
+                offset_y,
+                alpha,
+                &assets.char_bodies,
+                show_hp,
+            );
+        }
+        if let Some(boss) = self.boss.as_ref() {
+            boss.draw_scaled(scale, offset_x, offset_y, alpha, &assets.char_bodies);
+        }
+
+        if self.debug_colliders {
+            self.draw_colliders(scale, offset_x, offset_y);
+        }
+
+        // Draw the charging ring, filling in and warming up as it grows
+        if self.charge.is_some() {
+            let level = self.charge_level();
+            let radius = self.charged_radius(level) * scale;
+            let cx = offset_x + self.last_mouse_x * scale;
+            let cy = offset_y + self.last_mouse_y * scale;
+            let color = Color::new(1.0, 1.0 - 0.35 * level, 1.0 - level, 0.5 + 0.5 * level);
+            let width = if level >= 1.0 {
+                // Fully charged: throb to say there is nothing more to gain
+                3.0 + 1.5 * (self.pulse_timer * 12.0).sin()
+            } else {
+                2.0
+            };
+            draw_circle(cx, cy, radius, Color::new(1.0, 0.65, 0.0, 0.08 * level));
+            draw_circle_lines(cx, cy, radius, width * scale.max(1.0), color);
         }
 
         // Draw explosions
         for e in self.explosions.iter() {
             let alpha = 1.0 - (e.timer / 0.2);
-            draw_circle_lines(e.x, e.y, e.radius, 3.0, Color::new(1.0, 0.647, 0.0, alpha));
+            draw_circle_lines(
+                offset_x + e.x * scale,
+                offset_y + e.y * scale,
+                e.radius * scale,
+                3.0,
+                Color::new(1.0, 0.647, 0.0, alpha),
+            );
         }
 
-        // Draw particle emitters
-        for (emitter, pos) in self.emitters.iter_mut() {
-            emitter.draw(*pos);
+        // Draw score callouts
+        for t in self.floating_texts.iter() {
+            UI::draw_floating_text(
+                &t.text,
+                t.x,
+                t.y,
+                1.0 - t.timer / FLOATING_TEXT_TIME,
+                scale,
+                offset_x,
+                offset_y,
+                assets,
+            );
+        }
+
+        // Draw particle emitters. Emitters advance themselves by the frame time
+        // whenever they are drawn, so while paused a still is shown instead.
+        if self.scene == Scene::Paused {
+            self.draw_frozen_particles();
+        } else {
+            self.frozen_particles = None;
+            for (emitter, pos) in self.emitters.iter_mut() {
+                emitter.draw(*pos);
+            }
         }
 
         // Draw UI
         self.render_ui(scale, offset_x, offset_y, assets);
 
-        // Draw Game Over if needed
-        if self.health <= 0.0 {
-            UI::draw_game_over(scale, offset_x, offset_y, assets);
+        if self.scene != Scene::GameOver && self.waves.showing_banner() {
+            UI::draw_wave_banner(
+                self.waves.wave_number,
+                self.wave_script.is_boss_wave(self.waves.wave_number),
+                self.waves.phase_timer / BANNER_TIME,
+                scale,
+                offset_x,
+                offset_y,
+                assets,
+            );
         }
+    }
 
-        // Custom cursor - Light from Teardrop
-        let cursor_center_x = raw_mx;
-        let cursor_center_y = raw_my;
+    // The first paused frame draws the emitters one last time and grabs the
+    // screen; later paused frames lay that still over the (equally frozen)
+    // world. A resize while paused takes a new still.
+    fn draw_frozen_particles(&mut self) {
+        if self.emitters.is_empty() {
+            return;
+        }
+        let (w, h) = (screen_width(), screen_height());
+        if let Some(still) = self.frozen_particles.as_ref()
+            && still.size() == vec2(w, h)
+        {
+            draw_texture_ex(
+                still,
+                0.0,
+                0.0,
+                WHITE,
+                DrawTextureParams {
+                    // Framebuffer rows run bottom to top
+                    flip_y: true,
+                    ..Default::default()
+                },
+            );
+            return;
+        }
+
+        // Each emitter flushes the batched world before drawing itself, so
+        // the grab sees everything drawn so far
+        for (emitter, pos) in self.emitters.iter_mut() {
+            emitter.draw(*pos);
+        }
+        let (tw, th) = (w as u16, h as u16);
+        let still = Texture2D::from_rgba8(tw, th, &vec![0; tw as usize * th as usize * 4]);
+        still.grab_screen();
+        self.frozen_particles = Some(still);
+    }
+
+    // Debug view: hit shapes as the simulation sees them this step, plus the
+    // cursor's hot spot and the current explosion reach
+    fn draw_colliders(&self, scale: f32, offset_x: f32, offset_y: f32) {
+        let pulse_aware = self.tuning.pulse_hitboxes;
+        for popup in self.popups.iter() {
+            popup
+                .collider(pulse_aware)
+                .draw_debug(scale, offset_x, offset_y, GREEN);
+        }
+        for spawn in self.incoming.iter() {
+            spawn
+                .popup
+                .collider(pulse_aware)
+                .draw_debug(scale, offset_x, offset_y, GRAY);
+        }
+        if let Some(boss) = self.boss.as_ref() {
+            boss.collider()
+                .draw_debug(scale, offset_x, offset_y, MAGENTA);
+        }
+
+        let cx = offset_x + self.last_mouse_x * scale;
+        let cy = offset_y + self.last_mouse_y * scale;
+        draw_circle(cx, cy, 2.0 * scale, RED);
+        let reach = self.charged_radius(self.charge_level());
+        draw_circle_lines(cx, cy, reach * scale, 1.0, ORANGE);
+    }
+
+    fn draw_cursor(&self, scale: f32, offset_x: f32, offset_y: f32) {
+        // Custom cursor - Light from Teardrop (last cursor position fed to update)
+        let cursor_center_x = offset_x + self.last_mouse_x * scale;
+        let cursor_center_y = offset_y + self.last_mouse_y * scale;
 
         // Draw cursor trail from center of light
         for (x, y, age) in self.cursor_trail.iter() {
             let alpha = 1.0 - (age / 0.3); // Fade out over time
             let trail_radius = 4.0 * scale * alpha;
             // Trail comes from center of screen, not cursor position
-            let dx = *x - cursor_center_x;
-            let dy = *y - cursor_center_y;
+            let dx = offset_x + *x * scale - cursor_center_x;
+            let dy = offset_y + *y * scale - cursor_center_y;
             let trail_x = cursor_center_x + dx * 0.3; // Reduced distance for center origin
             let trail_y = cursor_center_y + dy * 0.3;
             draw_circle(trail_x, trail_y, trail_radius, Color::new(1.0, 0.8, 0.0, alpha * 0.5));
@@ -321,11 +1119,22 @@ impl GameState {
         // Draw light effect as a simple glow (no circles)
         let light_radius = 60.0 * scale;
         for i in 1..=5 {
-            let radius = (light_radius * i as f32 / 5.0) as f32;
+            let radius = light_radius * i as f32 / 5.0;
             let alpha = 0.06 * (1.0 - (i as f32 / 5.0).powi(2));
             draw_circle(cursor_center_x, cursor_center_y, radius, Color::new(1.0, 0.9, 0.4, alpha));
         }
 
+        // Shield bubble while the power-up lasts
+        if self.effects.shield > 0.0 {
+            draw_circle_lines(
+                cursor_center_x,
+                cursor_center_y,
+                22.0 * scale,
+                2.0,
+                Color::new(0.4, 0.75, 1.0, 0.8),
+            );
+        }
+
         // Bright core light
         draw_circle(cursor_center_x, cursor_center_y, 10.0 * scale, Color::new(1.0, 0.95, 0.6, 0.6));
         draw_circle(cursor_center_x, cursor_center_y, 6.0 * scale, Color::new(1.0, 1.0, 0.8, 0.8));
@@ -335,34 +1144,54 @@ impl GameState {
         draw_teardrop(cursor_center_x, cursor_center_y, 12.0 * scale, self.movement_direction, teardrop_color);
     }
 
-    fn get_scaling(&self) -> (f32, f32, f32) {
-        let scale_x = screen_width() / VIRTUAL_W;
-        let scale_y = screen_height() / VIRTUAL_H;
-        let scale = scale_x.min(scale_y);
-
-        let offset_x = (screen_width() - VIRTUAL_W * scale) / 2.0;
-        let offset_y = (screen_height() - VIRTUAL_H * scale) / 2.0;
-        (scale, offset_x, offset_y)
-    }
-
     fn render_ui(&self, scale: f32, offset_x: f32, offset_y: f32, assets: &GameAssets) {
         let bar_w = 140.0 * scale;
         let bar_h = 24.0 * scale;
         let ui_x = offset_x + VIRTUAL_W * scale - bar_w - 20.0 * scale;
         let ui_y = offset_y + 20.0 * scale;
 
-        // Health Bar
-        UI::draw_bar(
-            ui_x,
-            ui_y,
-            bar_w,
-            bar_h,
-            self.health,
-            "HEALTH",
-            RED,
-            assets,
-            scale,
-        );
+        // Boss health across the top
+        if let Some(boss) = self.boss.as_ref() {
+            let boss_w = 300.0 * scale;
+            UI::draw_bar(
+                offset_x + (VIRTUAL_W * scale - boss_w) / 2.0,
+                ui_y,
+                boss_w,
+                20.0 * scale,
+                (boss.hp / boss.max_hp).clamp(0.0, 1.0),
+                if boss.shielded() {
+                    "BOSS - SHIELDED"
+                } else {
+                    "BOSS"
+                },
+                RED,
+                assets,
+                scale,
+            );
+        }
+
+        // Health Bar, or the mode's name where there is no health to show
+        if self.mode.shows_health() {
+            UI::draw_bar(
+                ui_x,
+                ui_y,
+                bar_w,
+                bar_h,
+                self.health,
+                "HEALTH",
+                RED,
+                assets,
+                scale,
+            );
+        } else {
+            UI::draw_mode_badge(self.mode, ui_x, ui_y + bar_h, scale, assets);
+        }
+
+        // Time attack clock, under the boss bar when there is one
+        if let Some(left) = self.time_left() {
+            let clock_y = if self.boss.is_some() { 90.0 } else { 60.0 };
+            UI::draw_countdown(left, clock_y, scale, offset_x, offset_y, assets);
+        }
 
         // Energy Bar
         let energy_y = ui_y + bar_h + 40.0 * scale;
@@ -377,10 +1206,32 @@ impl GameState {
             assets,
             scale,
         );
+        // While charging, shade the part of the bar the release will spend
+        if self.charge.is_some() {
+            let cost = self.charged_cost(self.charge_level()).min(self.energy);
+            draw_rectangle(
+                ui_x + bar_w * (self.energy - cost),
+                energy_y,
+                bar_w * cost,
+                bar_h,
+                Color::new(1.0, 0.3, 0.0, 0.55),
+            );
+        }
+
+        // Power-up timers
+        UI::draw_effect_timers(
+            &self.effects.timers(),
+            ui_x,
+            energy_y + bar_h + 40.0 * scale,
+            scale,
+            assets,
+        );
 
         // Score & Count
         UI::draw_score_and_popups(
             self.score,
+            self.combo.multiplier(),
+            self.combo.streak,
             self.popups.len(),
             scale,
             offset_x,
@@ -389,3 +1240,20 @@ impl GameState {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blast_damage_tapers_to_the_edge() {
+        assert_eq!(blast_damage(2.0, 0.3, 0.0, 90.0), 2.0);
+        assert!((blast_damage(2.0, 0.3, 90.0 * 90.0, 90.0) - 1.4).abs() < 1e-5);
+        assert!((blast_damage(2.0, 0.3, 45.0 * 45.0, 90.0) - 1.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn zero_radius_blast_deals_full_damage() {
+        assert_eq!(blast_damage(1.0, 0.3, 0.0, 0.0), 1.0);
+    }
+}
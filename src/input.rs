@@ -0,0 +1,217 @@
+use crate::game::get_scaling;
+use macroquad::input::utils::{register_input_subscriber, repeat_all_miniquad_input};
+use macroquad::miniquad::EventHandler;
+use macroquad::prelude::*;
+
+// A frame longer than this means the app was not running (minimised,
+// dragged, suspended) and is treated the same as losing focus.
+const STALL_THRESHOLD: f32 = 0.5;
+
+/// Everything the simulation needs to know about the player for one frame.
+/// The cursor is in virtual (800x600) space, not raw screen pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameInput {
+    pub cursor_x: f32,
+    pub cursor_y: f32,
+    pub explode: bool, // Right button released: set off the explosion
+    pub charge: bool,  // Right button held: charging an explosion
+    pub zap: bool,
+    pub restart: bool,
+    pub confirm: bool,
+    pub pause: bool,
+    pub menu_up: bool, // Title screen mode selection
+    pub menu_down: bool,
+    pub focus_lost: bool, // Forces a pause; unlike `pause` it never resumes
+}
+
+impl FrameInput {
+    // Fold a newer sample into this one: take the latest cursor and keep any
+    // press that has not been consumed by a simulation step yet.
+    pub fn merge(&mut self, newer: &FrameInput) {
+        self.cursor_x = newer.cursor_x;
+        self.cursor_y = newer.cursor_y;
+        self.charge = newer.charge;
+        self.explode |= newer.explode;
+        self.zap |= newer.zap;
+        self.restart |= newer.restart;
+        self.confirm |= newer.confirm;
+        self.pause |= newer.pause;
+        self.menu_up |= newer.menu_up;
+        self.menu_down |= newer.menu_down;
+        self.focus_lost |= newer.focus_lost;
+    }
+
+    // Presses only apply to the first simulation step that sees them
+    pub fn clear_presses(&mut self) {
+        self.explode = false;
+        self.zap = false;
+        self.restart = false;
+        self.confirm = false;
+        self.pause = false;
+        self.menu_up = false;
+        self.menu_down = false;
+        self.focus_lost = false;
+    }
+}
+
+pub trait InputSource {
+    fn poll(&mut self) -> FrameInput;
+}
+
+// Listens to window events macroquad does not expose as polling functions
+#[derive(Default)]
+struct WindowWatcher {
+    minimized: bool,
+}
+
+impl EventHandler for WindowWatcher {
+    fn update(&mut self) {}
+
+    fn draw(&mut self) {}
+
+    fn window_minimized_event(&mut self) {
+        self.minimized = true;
+    }
+}
+
+// Live input from the macroquad window. Must be created after the window is up.
+pub struct MacroquadInput {
+    subscriber: usize,
+    watcher: WindowWatcher,
+}
+
+impl MacroquadInput {
+    pub fn new() -> Self {
+        Self {
+            subscriber: register_input_subscriber(),
+            watcher: WindowWatcher::default(),
+        }
+    }
+}
+
+impl Default for MacroquadInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputSource for MacroquadInput {
+    fn poll(&mut self) -> FrameInput {
+        // miniquad has no focus event, so minimising or a stalled frame is the
+        // closest signal we get for "the player went away". Its pointer-leave
+        // event only reaches macroquad's own handler, so alt-tabbing away
+        // from a visible window goes unnoticed (see README).
+        self.watcher.minimized = false;
+        repeat_all_miniquad_input(&mut self.watcher, self.subscriber);
+        let focus_lost = self.watcher.minimized || get_frame_time() > STALL_THRESHOLD;
+
+        let (raw_mx, raw_my) = mouse_position();
+        let (scale, offset_x, offset_y) = get_scaling();
+
+        FrameInput {
+            cursor_x: (raw_mx - offset_x) / scale,
+            cursor_y: (raw_my - offset_y) / scale,
+            explode: is_mouse_button_released(MouseButton::Right),
+            charge: is_mouse_button_down(MouseButton::Right),
+            zap: is_mouse_button_pressed(MouseButton::Left),
+            restart: is_key_pressed(KeyCode::R),
+            confirm: is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space),
+            pause: is_key_pressed(KeyCode::Escape),
+            menu_up: is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W),
+            menu_down: is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S),
+            focus_lost,
+        }
+    }
+}
+
+// Pre-recorded input, one entry per frame. Once the script runs out the
+// cursor stays where it was and no buttons are pressed.
+pub struct ScriptedInput {
+    frames: Vec<FrameInput>,
+    index: usize,
+}
+
+impl ScriptedInput {
+    pub fn new(frames: Vec<FrameInput>) -> Self {
+        Self { frames, index: 0 }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.frames.len()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self) -> FrameInput {
+        if let Some(frame) = self.frames.get(self.index) {
+            self.index += 1;
+            return *frame;
+        }
+
+        match self.frames.last() {
+            Some(last) => FrameInput {
+                cursor_x: last.cursor_x,
+                cursor_y: last.cursor_y,
+                ..Default::default()
+            },
+            None => FrameInput::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_unconsumed_presses_and_the_latest_cursor() {
+        let mut pending = FrameInput {
+            explode: true,
+            ..FrameInput::default()
+        };
+        pending.merge(&FrameInput {
+            cursor_x: 10.0,
+            cursor_y: 20.0,
+            charge: true,
+            zap: true,
+            ..FrameInput::default()
+        });
+        assert_eq!((pending.cursor_x, pending.cursor_y), (10.0, 20.0));
+        assert!(pending.explode && pending.zap && pending.charge);
+
+        pending.clear_presses();
+        assert!(!pending.explode && !pending.zap);
+        // Held buttons and the cursor are state, not presses
+        assert!(pending.charge);
+        assert_eq!(pending.cursor_x, 10.0);
+    }
+
+    #[test]
+    fn scripted_input_holds_the_last_cursor_once_finished() {
+        let mut input = ScriptedInput::new(vec![
+            FrameInput {
+                cursor_x: 1.0,
+                explode: true,
+                ..FrameInput::default()
+            },
+            FrameInput {
+                cursor_x: 2.0,
+                cursor_y: 3.0,
+                zap: true,
+                ..FrameInput::default()
+            },
+        ]);
+        assert_eq!(input.poll().cursor_x, 1.0);
+        assert!(!input.is_finished());
+        assert!(input.poll().zap);
+        assert!(input.is_finished());
+        assert_eq!(
+            input.poll(),
+            FrameInput {
+                cursor_x: 2.0,
+                cursor_y: 3.0,
+                ..FrameInput::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Cursor Crisis as a library: the simulation, procedural audio and drawing
+//! helpers behind the game binary, reusable by tools such as the headless
+//! simulator.
+//!
+//! The simulation (`GameState`, `Popup`, `FrameInput`, `GameRng`, `Tuning`)
+//! never touches the window or audio device, so it runs headless. Anything
+//! that draws or plays sound (`GameAssets`, `UI`, `GameState::draw`,
+//! `GameState::flush_effects`) needs a macroquad window. Sound is behind the
+//! default `audio` feature; without it `GameAssets::play` does nothing.
+
+pub mod app;
+pub mod assets;
+pub mod behavior;
+pub mod boss;
+pub mod cli;
+pub mod collider;
+pub mod config;
+pub mod difficulty;
+pub mod flocking;
+pub mod game;
+pub mod input;
+pub mod modes;
+pub mod pickups;
+pub mod popup;
+pub mod replay;
+pub mod rng;
+pub mod scoring;
+pub mod snapshot;
+pub mod sound_gen;
+pub mod spawning;
+pub mod timestep;
+pub mod tuning;
+pub mod ui;
+pub mod waves;
+
+pub use assets::GameAssets;
+pub use game::{ExplosionCircle, GameState, RunStats, Scene, VIRTUAL_H, VIRTUAL_W};
+pub use input::{FrameInput, InputSource, MacroquadInput, ScriptedInput};
+pub use popup::Popup;
+pub use replay::Replay;
+pub use rng::GameRng;
+pub use sound_gen::WavGenerator;
+pub use timestep::{FixedTimestep, SIM_DT, SIM_HZ};
+pub use tuning::Tuning;
+pub use ui::UI;
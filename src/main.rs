@@ -1,31 +1,22 @@
 // Hide console window on Windows release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod assets;
-mod config;
-mod game;
-mod popup;
-pub mod sound_gen;
-mod ui;
-
-use assets::GameAssets;
-use config::window_conf;
-use game::GameState;
-use macroquad::prelude::*;
+use cursor_crisis::app;
+use cursor_crisis::cli::{LaunchOptions, USAGE};
+use cursor_crisis::config::window_conf;
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let assets = GameAssets::load().await;
-    let mut game = GameState::new();
-
-    show_mouse(false);
-
-    loop {
-        let dt = get_frame_time();
-
-        game.update(dt, &assets);
-        game.draw(&assets);
+    let options = match LaunchOptions::from_args() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
 
-        next_frame().await;
+    if let Err(err) = app::run(options).await {
+        eprintln!("{}", err);
+        std::process::exit(1);
     }
 }
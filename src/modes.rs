@@ -0,0 +1,150 @@
+use crate::pickups::PickupKind;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Length of a time attack run, in seconds
+pub const TIME_ATTACK_LENGTH: f32 = 120.0;
+// The countdown turns red for the last few seconds
+pub const TIME_ATTACK_WARNING: f32 = 10.0;
+
+// Rules a run is played under. Picked on the title screen or with --mode;
+// restarting keeps the mode of the last run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    #[default]
+    Endless, // Survive as long as possible on one health bar
+    TimeAttack, // Highest score before the clock runs out
+    OneHit,     // Any contact ends the run
+    Zen,        // Nothing hurts; practice explosions and chains
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Endless,
+        GameMode::TimeAttack,
+        GameMode::OneHit,
+        GameMode::Zen,
+    ];
+
+    // Names accepted by --mode
+    pub const NAMES: [&'static str; 4] = ["endless", "time-attack", "one-hit", "zen"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .position(|n| *n == name)
+            .map(|i| Self::ALL[i])
+    }
+
+    pub fn name(self) -> &'static str {
+        Self::NAMES[self.index()]
+    }
+
+    // Position in `ALL`; also how replays store the mode
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|m| *m == self).unwrap_or(0)
+    }
+
+    // Neighbouring mode in the title screen list, wrapping around
+    pub fn step(self, delta: i32) -> Self {
+        let count = Self::ALL.len() as i32;
+        Self::ALL[(self.index() as i32 + delta).rem_euclid(count) as usize]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Endless => "ENDLESS",
+            GameMode::TimeAttack => "TIME ATTACK",
+            GameMode::OneHit => "ONE HIT",
+            GameMode::Zen => "ZEN",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            GameMode::Endless => "SURVIVE AS LONG AS YOU CAN",
+            GameMode::TimeAttack => "SCORE AS MUCH AS YOU CAN IN 2 MINUTES",
+            GameMode::OneHit => "ONE TOUCH AND IT'S OVER",
+            GameMode::Zen => "NOTHING HURTS - PRACTICE YOUR BLASTS",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            GameMode::Endless => WHITE,
+            GameMode::TimeAttack => YELLOW,
+            GameMode::OneHit => RED,
+            GameMode::Zen => SKYBLUE,
+        }
+    }
+
+    // Runs in this mode end when the clock reaches this many seconds
+    pub fn time_limit(self) -> Option<f32> {
+        match self {
+            GameMode::TimeAttack => Some(TIME_ATTACK_LENGTH),
+            _ => None,
+        }
+    }
+
+    // Health actually lost for `drain` worth of contact this step
+    pub fn contact_damage(self, drain: f32) -> f32 {
+        match self {
+            GameMode::Endless | GameMode::TimeAttack => drain,
+            GameMode::OneHit if drain > 0.0 => 1.0,
+            GameMode::OneHit | GameMode::Zen => 0.0,
+        }
+    }
+
+    // Power-ups destroyed popups may drop; ones that would do nothing in
+    // this mode are left out
+    pub fn drops(self) -> &'static [PickupKind] {
+        match self {
+            GameMode::Endless | GameMode::TimeAttack => &PickupKind::ALL,
+            GameMode::OneHit => &[
+                PickupKind::Energy,
+                PickupKind::Shield,
+                PickupKind::SlowTime,
+                PickupKind::BigBlast,
+            ],
+            GameMode::Zen => &[
+                PickupKind::Energy,
+                PickupKind::SlowTime,
+                PickupKind::BigBlast,
+            ],
+        }
+    }
+
+    // Only modes with a gradual drain have a health bar worth showing
+    pub fn shows_health(self) -> bool {
+        matches!(self, GameMode::Endless | GameMode::TimeAttack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_parse_back_to_their_mode() {
+        for mode in GameMode::ALL {
+            assert_eq!(GameMode::parse(mode.name()), Some(mode));
+        }
+        assert_eq!(GameMode::parse("hardcore"), None);
+    }
+
+    #[test]
+    fn step_wraps_around() {
+        assert_eq!(GameMode::Endless.step(-1), GameMode::Zen);
+        assert_eq!(GameMode::Zen.step(1), GameMode::Endless);
+    }
+
+    #[test]
+    fn drops_leave_out_power_ups_that_do_nothing() {
+        assert!(!GameMode::OneHit.drops().contains(&PickupKind::Health));
+        assert!(GameMode::OneHit.drops().contains(&PickupKind::Shield));
+        assert!(!GameMode::Zen.drops().contains(&PickupKind::Health));
+        assert!(!GameMode::Zen.drops().contains(&PickupKind::Shield));
+        assert_eq!(GameMode::Endless.drops(), &PickupKind::ALL);
+    }
+}
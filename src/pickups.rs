@@ -0,0 +1,170 @@
+use crate::rng::GameRng;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const PICKUP_RADIUS: f32 = 14.0;
+pub const PICKUP_LIFETIME: f32 = 8.0;
+const PICKUP_BLINK_TIME: f32 = 2.0; // Blinks for this long before vanishing
+
+pub const HEALTH_RESTORE: f32 = 0.35;
+pub const SLOW_TIME_FACTOR: f32 = 0.5; // Popup speed while slow-time is active
+pub const BIG_BLAST_FACTOR: f32 = 1.5; // Explosion radius multiplier
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickupKind {
+    Energy,
+    Health,
+    Shield,
+    SlowTime,
+    BigBlast,
+}
+
+impl PickupKind {
+    pub const ALL: [PickupKind; 5] = [
+        PickupKind::Energy,
+        PickupKind::Health,
+        PickupKind::Shield,
+        PickupKind::SlowTime,
+        PickupKind::BigBlast,
+    ];
+
+    // Pick one kind from a drop table
+    pub fn random(table: &[PickupKind], rng: &mut GameRng) -> Self {
+        table[rng.gen_range_usize(0, table.len())]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PickupKind::Energy => "ENERGY",
+            PickupKind::Health => "HEALTH",
+            PickupKind::Shield => "SHIELD",
+            PickupKind::SlowTime => "SLOW",
+            PickupKind::BigBlast => "BIG BLAST",
+        }
+    }
+
+    // Seconds the effect lasts; zero for instant pickups
+    pub fn duration(self) -> f32 {
+        match self {
+            PickupKind::Energy | PickupKind::Health => 0.0,
+            PickupKind::Shield => 6.0,
+            PickupKind::SlowTime => 5.0,
+            PickupKind::BigBlast => 8.0,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            PickupKind::Energy => YELLOW,
+            PickupKind::Health => RED,
+            PickupKind::Shield => SKYBLUE,
+            PickupKind::SlowTime => VIOLET,
+            PickupKind::BigBlast => ORANGE,
+        }
+    }
+}
+
+// A pickup lying in the arena, in virtual space
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pickup {
+    pub x: f32,
+    pub y: f32,
+    pub kind: PickupKind,
+    pub age: f32,
+}
+
+impl Pickup {
+    pub fn new(x: f32, y: f32, kind: PickupKind) -> Self {
+        Self {
+            x,
+            y,
+            kind,
+            age: 0.0,
+        }
+    }
+
+    // Ages the pickup; returns false once it has timed out
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.age += dt;
+        self.age < PICKUP_LIFETIME
+    }
+
+    pub fn touches(&self, px: f32, py: f32) -> bool {
+        let dx = px - self.x;
+        let dy = py - self.y;
+        dx * dx + dy * dy <= PICKUP_RADIUS * PICKUP_RADIUS
+    }
+
+    pub fn draw_scaled(&self, scale: f32, ox: f32, oy: f32, font: &Font) {
+        // Blink for the last couple of seconds before it expires
+        let left = PICKUP_LIFETIME - self.age;
+        if left < PICKUP_BLINK_TIME && (left * 8.0).sin() < 0.0 {
+            return;
+        }
+
+        let cx = ox + self.x * scale;
+        let cy = oy + self.y * scale;
+        let color = self.kind.color();
+        draw_circle(
+            cx,
+            cy,
+            PICKUP_RADIUS * scale,
+            Color::new(0.0, 0.0, 0.0, 0.6),
+        );
+        draw_circle_lines(cx, cy, PICKUP_RADIUS * scale, 2.0, color);
+
+        let letter = &self.kind.label()[..1];
+        let font_size = (16.0 * scale) as u16;
+        let size = measure_text(letter, Some(font), font_size, 1.0);
+        draw_text_ex(
+            letter,
+            cx - size.width / 2.0,
+            cy + size.height / 2.0,
+            TextParams {
+                font: Some(font),
+                font_size,
+                color,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Seconds left on each timed power-up; zero means inactive
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActiveEffects {
+    pub shield: f32,
+    pub slow_time: f32,
+    pub big_blast: f32,
+}
+
+impl ActiveEffects {
+    pub fn update(&mut self, dt: f32) {
+        self.shield = (self.shield - dt).max(0.0);
+        self.slow_time = (self.slow_time - dt).max(0.0);
+        self.big_blast = (self.big_blast - dt).max(0.0);
+    }
+
+    // Start (or restart) a timed effect
+    pub fn activate(&mut self, kind: PickupKind) {
+        let timer = match kind {
+            PickupKind::Shield => &mut self.shield,
+            PickupKind::SlowTime => &mut self.slow_time,
+            PickupKind::BigBlast => &mut self.big_blast,
+            PickupKind::Energy | PickupKind::Health => return,
+        };
+        *timer = kind.duration();
+    }
+
+    // Active effects with their remaining time, for the HUD
+    pub fn timers(&self) -> Vec<(PickupKind, f32)> {
+        [
+            (PickupKind::Shield, self.shield),
+            (PickupKind::SlowTime, self.slow_time),
+            (PickupKind::BigBlast, self.big_blast),
+        ]
+        .into_iter()
+        .filter(|(_, t)| *t > 0.0)
+        .collect()
+    }
+}
@@ -1,32 +1,132 @@
+use crate::behavior::{BehaviorKind, PopupBehavior, SteerContext};
+use crate::collider::Collider;
+use crate::difficulty::DifficultyLevel;
+use crate::game::{VIRTUAL_H, VIRTUAL_W};
+use crate::rng::GameRng;
+use crate::tuning::Tuning;
 use macroquad::prelude::*;
-use macroquad::rand::gen_range;
+use serde::{Deserialize, Serialize};
 
+// Which character bodies a popup may use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyShape {
+    #[default]
+    Any,
+    Squircle,
+    Rhombus,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Popup {
     pub x: f32,
     pub y: f32,
+    pub prev_x: f32, // Position at the previous simulation step, for render interpolation
+    pub prev_y: f32,
     pub w: f32,
     pub h: f32,
     pub speed: f32,
     pub char_index: usize,
     pub scale_timer: f32,
+    pub behavior: PopupBehavior,
+    pub hp: f32,
+    pub max_hp: f32,
+    pub hit_flash: f32, // Seconds of flash left after taking a non-lethal hit
 }
 
+// How long a popup flashes after being hit
+const HIT_FLASH_TIME: f32 = 0.15;
+const HIT_FLASH_COLOR: Color = Color::new(1.0, 0.35, 0.35, 1.0);
+
+// Health bar drawn above tough popups, in virtual pixels
+const HP_BAR_HEIGHT: f32 = 4.0;
+const HP_BAR_GAP: f32 = 3.0;
+
 impl Popup {
-    pub fn new(max_w: f32, max_h: f32, num_chars: usize) -> Self {
-        let size = gen_range(40.0, 60.0);
+    pub fn new(
+        max_w: f32,
+        max_h: f32,
+        kind: BehaviorKind,
+        tuning: &Tuning,
+        level: &DifficultyLevel,
+        rng: &mut GameRng,
+    ) -> Self {
+        let base_size = rng.gen_range_f32(tuning.popup_size_min, tuning.popup_size_max);
+        let size = base_size * level.size;
+        // Bigger popups are sturdier
+        let size_span = tuning.popup_size_max - tuning.popup_size_min;
+        let size_t = if size_span > 0.0 {
+            (base_size - tuning.popup_size_min) / size_span
+        } else {
+            0.0
+        };
+        let max_hp = (tuning.popup_hp_min + (tuning.popup_hp_max - tuning.popup_hp_min) * size_t)
+            * kind.toughness();
+        let bodies = kind.bodies();
+        let x = rng.gen_range_f32(0.0, max_w - size);
+        let y = rng.gen_range_f32(0.0, max_h - size);
         Self {
-            x: gen_range(0.0, max_w - size),
-            y: gen_range(0.0, max_h - size),
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
             w: size,
             h: size,
-            speed: gen_range(40.0, 110.0),
-            char_index: gen_range(0, num_chars),
-            scale_timer: gen_range(0.0, 2.0 * std::f32::consts::PI), // Random start phase
+            speed: rng.gen_range_f32(tuning.popup_speed_min, tuning.popup_speed_max) * level.speed,
+            char_index: bodies[rng.gen_range_usize(0, bodies.len())],
+            scale_timer: rng.gen_range_f32(0.0, 2.0 * std::f32::consts::PI), // Random start phase
+            behavior: PopupBehavior::new(kind, rng),
+            hp: max_hp,
+            max_hp,
+            hit_flash: 0.0,
         }
     }
 
+    // Move the popup so its centre sits at (cx, cy), kept inside the arena
+    pub fn center_on(&mut self, cx: f32, cy: f32, max_w: f32, max_h: f32) {
+        self.x = (cx - self.w / 2.0).clamp(0.0, max_w - self.w);
+        self.y = (cy - self.h / 2.0).clamp(0.0, max_h - self.h);
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+
+    // Like `center_on` but free to leave the arena, for popups entering from
+    // off-screen
+    pub fn move_center_to(&mut self, cx: f32, cy: f32) {
+        self.x = cx - self.w / 2.0;
+        self.y = cy - self.h / 2.0;
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+
     pub fn update(&mut self, dt: f32) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
         self.scale_timer += dt;
+        self.hit_flash = (self.hit_flash - dt).max(0.0);
+    }
+
+    // Apply damage; returns true when this finishes the popup off
+    pub fn take_damage(&mut self, amount: f32) -> bool {
+        self.hp -= amount;
+        if self.hp <= 0.0 {
+            return true;
+        }
+        self.hit_flash = HIT_FLASH_TIME;
+        false
+    }
+
+    // Popups that survive a dead-centre blast get a health bar
+    pub fn is_tough(&self, full_damage: f32) -> bool {
+        self.max_hp > full_damage
+    }
+
+    // Position blended between the last two simulation steps
+    pub fn render_pos(&self, alpha: f32) -> (f32, f32) {
+        (
+            self.prev_x + (self.x - self.prev_x) * alpha,
+            self.prev_y + (self.y - self.prev_y) * alpha,
+        )
     }
 
     pub fn get_scale_multiplier(&self) -> f32 {
@@ -34,22 +134,111 @@ impl Popup {
         let pulse = (self.scale_timer * 5.0).sin();
         0.85 + (pulse + 1.0) * 0.15
     }
-    pub fn follow(&mut self, target_x: f32, target_y: f32, dt: f32) {
-        let dx = target_x - (self.x + self.w / 2.0);
-        let dy = target_y - (self.y + self.h / 2.0);
-
-        let dist = (dx * dx + dy * dy).sqrt();
-        if dist > 1.0 {
-            self.x += (dx / dist) * self.speed * dt;
-            self.y += (dy / dist) * self.speed * dt;
+    // Move according to this popup's behaviour; `target_vx/vy` is the cursor velocity
+    pub fn follow(
+        &mut self,
+        target_x: f32,
+        target_y: f32,
+        target_vx: f32,
+        target_vy: f32,
+        dt: f32,
+    ) {
+        let ctx = SteerContext {
+            pos: vec2(self.x + self.w / 2.0, self.y + self.h / 2.0),
+            speed: self.speed,
+            target: vec2(target_x, target_y),
+            target_velocity: vec2(target_vx, target_vy),
+            dt,
+        };
+        let velocity = self.behavior.as_behavior_mut().steer(&ctx);
+        self.x += velocity.x * dt;
+        self.y += velocity.y * dt;
+    }
+
+    // Hit shape matching the body: a circle for squircles, a diamond for
+    // rhombuses. With `pulse_aware` it grows and shrinks with the sprite.
+    pub fn collider(&self, pulse_aware: bool) -> Collider {
+        let center = vec2(self.x + self.w / 2.0, self.y + self.h / 2.0);
+        let mut radius = self.w.min(self.h) / 2.0;
+        if pulse_aware {
+            radius *= self.get_scale_multiplier();
         }
+        match self.behavior.kind().shape() {
+            BodyShape::Rhombus => Collider::Diamond { center, radius },
+            BodyShape::Squircle | BodyShape::Any => Collider::Circle { center, radius },
+        }
+    }
+
+    // Squared distance from a point to the popup's collider (0 inside)
+    pub fn dist_sq_to(&self, px: f32, py: f32, pulse_aware: bool) -> f32 {
+        self.collider(pulse_aware).dist_sq_to(vec2(px, py))
+    }
+
+    pub fn hit(&self, mx: f32, my: f32, pulse_aware: bool) -> bool {
+        self.collider(pulse_aware).contains(vec2(mx, my))
     }
 
-    pub fn hit(&self, mx: f32, my: f32) -> bool {
-        mx > self.x && mx < self.x + self.w && my > self.y && my < self.y + self.h
+    // Spawn warning: a pulsing ghost of the body where the popup will appear,
+    // pulled inside the arena for popups entering from off-screen.
+    // `progress` runs 0..1 over the warning.
+    pub fn draw_telegraph(
+        &self,
+        scale: f32,
+        ox: f32,
+        oy: f32,
+        progress: f32,
+        char_textures: &[Texture2D],
+    ) {
+        let x = self.x.clamp(0.0, VIRTUAL_W - self.w);
+        let y = self.y.clamp(0.0, VIRTUAL_H - self.h);
+        let Some(tex) = char_textures.get(self.char_index) else {
+            return;
+        };
+
+        // Red silhouette behind the ghost reads as an outline; it pulses and
+        // closes in on the body as the spawn gets near
+        let pulse = (progress * 6.0 * std::f32::consts::PI).sin() * 0.5 + 0.5;
+        let grow = 3.0 + (1.0 - progress) * 10.0;
+        draw_texture_ex(
+            tex,
+            ox + (x - grow) * scale,
+            oy + (y - grow) * scale,
+            Color::new(1.0, 0.2, 0.2, 0.25 + 0.5 * pulse),
+            DrawTextureParams {
+                dest_size: Some(vec2(
+                    (self.w + grow * 2.0) * scale,
+                    (self.h + grow * 2.0) * scale,
+                )),
+                ..Default::default()
+            },
+        );
+        draw_texture_ex(
+            tex,
+            ox + x * scale,
+            oy + y * scale,
+            Color::new(0.1, 0.1, 0.1, 0.6),
+            DrawTextureParams {
+                dest_size: Some(vec2(self.w * scale, self.h * scale)),
+                ..Default::default()
+            },
+        );
     }
 
-    pub fn draw_scaled(&self, scale: f32, ox: f32, oy: f32, char_textures: &[Texture2D]) {
+    pub fn draw_scaled(
+        &self,
+        scale: f32,
+        ox: f32,
+        oy: f32,
+        alpha: f32,
+        char_textures: &[Texture2D],
+        show_hp: bool,
+    ) {
+        let (x, y) = self.render_pos(alpha);
+        let tint = if self.hit_flash > 0.0 {
+            HIT_FLASH_COLOR
+        } else {
+            WHITE
+        };
         if let Some(tex) = char_textures.get(self.char_index) {
             let scale_mult = self.get_scale_multiplier();
             let scaled_w = self.w * scale_mult;
@@ -59,9 +248,9 @@ impl Popup {
 
             draw_texture_ex(
                 tex,
-                ox + (self.x + offset_x) * scale,
-                oy + (self.y + offset_y) * scale,
-                WHITE,
+                ox + (x + offset_x) * scale,
+                oy + (y + offset_y) * scale,
+                tint,
                 DrawTextureParams {
                     dest_size: Some(vec2(scaled_w * scale, scaled_h * scale)),
                     ..Default::default()
@@ -70,12 +259,32 @@ impl Popup {
         } else {
             // Fallback to rectangle if texture index is invalid
             draw_rectangle(
-                ox + self.x * scale,
-                oy + self.y * scale,
+                ox + x * scale,
+                oy + y * scale,
                 self.w * scale,
                 self.h * scale,
                 GRAY,
             );
         }
+
+        if show_hp {
+            let bar_x = ox + x * scale;
+            let bar_y = oy + (y - HP_BAR_GAP - HP_BAR_HEIGHT) * scale;
+            let fill = (self.hp / self.max_hp).clamp(0.0, 1.0);
+            draw_rectangle(
+                bar_x,
+                bar_y,
+                self.w * scale,
+                HP_BAR_HEIGHT * scale,
+                Color::new(0.0, 0.0, 0.0, 0.6),
+            );
+            draw_rectangle(
+                bar_x,
+                bar_y,
+                self.w * fill * scale,
+                HP_BAR_HEIGHT * scale,
+                Color::new(0.9, 0.2, 0.2, 1.0),
+            );
+        }
     }
 }
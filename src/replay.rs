@@ -0,0 +1,350 @@
+use crate::input::FrameInput;
+use crate::modes::GameMode;
+use crate::timestep::SIM_HZ;
+use crate::tuning::Tuning;
+use crate::waves::WaveScript;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Replay file layout (all little endian):
+//   magic      4 bytes  "CCRP"
+//   version    u16
+//   sim_hz     f32      simulation rate the inputs were recorded at
+//   seed       u64      GameRng seed
+//   mode       u8       game mode selected at launch (index into GameMode::ALL)
+//   tuning     u32 length, then that many bytes of JSON: the Tuning the run
+//              was played with, difficulty override included
+//   waves      u32 length, then that many bytes of JSON: the WaveScript
+//   frames     u32      number of input frames that follow
+//   per frame  f32 cursor_x, f32 cursor_y, u16 flags
+//              (bit 0 explode, bit 1 restart, bit 2 confirm, bit 3 pause,
+//               bit 4 focus lost, bit 5 zap, bit 6 charge, bit 7 menu up,
+//               bit 8 menu down)
+//
+// Version 2 added the confirm/pause flags and starts recording on the title screen.
+// Version 3 added the zap flag.
+// Version 4 added the charge flag; explode now marks the right button release.
+// Version 5 added the mode byte and menu flags, widening the flags to u16.
+// Version 6 embeds the tuning so replays no longer depend on tuning.toml.
+// Version 7 embeds the wave script as well.
+pub const REPLAY_MAGIC: &[u8; 4] = b"CCRP";
+pub const REPLAY_VERSION: u16 = 7;
+
+// Fixed part of the header, up to and including the mode byte
+const HEADER_LEN: usize = 4 + 2 + 4 + 8 + 1;
+const FRAME_LEN: usize = 4 + 4 + 2;
+
+const FLAG_EXPLODE: u16 = 1 << 0;
+const FLAG_RESTART: u16 = 1 << 1;
+const FLAG_CONFIRM: u16 = 1 << 2;
+const FLAG_PAUSE: u16 = 1 << 3;
+const FLAG_FOCUS_LOST: u16 = 1 << 4;
+const FLAG_ZAP: u16 = 1 << 5;
+const FLAG_CHARGE: u16 = 1 << 6;
+const FLAG_MENU_UP: u16 = 1 << 7;
+const FLAG_MENU_DOWN: u16 = 1 << 8;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u16),
+    SimRateMismatch(f32),
+    UnknownMode(u8),
+    BadSettings(String),
+    Truncated,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "replay I/O error: {}", err),
+            ReplayError::BadMagic => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {}", v),
+            ReplayError::SimRateMismatch(hz) => {
+                write!(
+                    f,
+                    "replay recorded at {} Hz, game runs at {} Hz",
+                    hz, SIM_HZ
+                )
+            }
+            ReplayError::UnknownMode(m) => write!(f, "unknown game mode {} in replay", m),
+            ReplayError::BadSettings(msg) => write!(f, "invalid replay settings: {}", msg),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+// Seed, starting mode, tuning and wave script plus one input per simulation
+// step: enough to rebuild a run exactly
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub tuning: Tuning,
+    pub waves: WaveScript,
+    pub frames: Vec<FrameInput>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, mode: GameMode, tuning: Tuning, waves: WaveScript) -> Self {
+        Self {
+            seed,
+            mode,
+            tuning,
+            waves,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, input: &FrameInput) {
+        self.frames.push(*input);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.frames.len() * FRAME_LEN);
+
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&SIM_HZ.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(self.mode.index() as u8);
+        let tuning = serde_json::to_vec(&self.tuning).expect("tuning always serializes");
+        write_blob(&mut bytes, &tuning);
+        let waves = serde_json::to_vec(&self.waves).expect("wave script always serializes");
+        write_blob(&mut bytes, &waves);
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        for frame in self.frames.iter() {
+            let mut flags = 0;
+            if frame.explode {
+                flags |= FLAG_EXPLODE;
+            }
+            if frame.restart {
+                flags |= FLAG_RESTART;
+            }
+            if frame.confirm {
+                flags |= FLAG_CONFIRM;
+            }
+            if frame.pause {
+                flags |= FLAG_PAUSE;
+            }
+            if frame.focus_lost {
+                flags |= FLAG_FOCUS_LOST;
+            }
+            if frame.zap {
+                flags |= FLAG_ZAP;
+            }
+            if frame.charge {
+                flags |= FLAG_CHARGE;
+            }
+            if frame.menu_up {
+                flags |= FLAG_MENU_UP;
+            }
+            if frame.menu_down {
+                flags |= FLAG_MENU_DOWN;
+            }
+            bytes.extend_from_slice(&frame.cursor_x.to_le_bytes());
+            bytes.extend_from_slice(&frame.cursor_y.to_le_bytes());
+            bytes.extend_from_slice(&flags.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < 4 || &bytes[0..4] != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(ReplayError::Truncated);
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let sim_hz = f32::from_le_bytes(bytes[6..10].try_into().unwrap());
+        if sim_hz != SIM_HZ {
+            return Err(ReplayError::SimRateMismatch(sim_hz));
+        }
+
+        let seed = u64::from_le_bytes(bytes[10..18].try_into().unwrap());
+        let mode = *GameMode::ALL
+            .get(bytes[18] as usize)
+            .ok_or(ReplayError::UnknownMode(bytes[18]))?;
+
+        let mut offset = HEADER_LEN;
+        let tuning: Tuning = serde_json::from_slice(read_blob(bytes, &mut offset)?)
+            .map_err(|err| ReplayError::BadSettings(err.to_string()))?;
+        tuning
+            .validate()
+            .map_err(|err| ReplayError::BadSettings(err.to_string()))?;
+        let waves: WaveScript = serde_json::from_slice(read_blob(bytes, &mut offset)?)
+            .map_err(|err| ReplayError::BadSettings(err.to_string()))?;
+        waves.validate().map_err(ReplayError::BadSettings)?;
+
+        let count = read_u32(bytes, &mut offset)? as usize;
+        let body = &bytes[offset..];
+        if body.len() < count * FRAME_LEN {
+            return Err(ReplayError::Truncated);
+        }
+
+        let frames = body
+            .chunks_exact(FRAME_LEN)
+            .take(count)
+            .map(|chunk| {
+                let flags = u16::from_le_bytes([chunk[8], chunk[9]]);
+                FrameInput {
+                    cursor_x: f32::from_le_bytes(chunk[0..4].try_into().unwrap()),
+                    cursor_y: f32::from_le_bytes(chunk[4..8].try_into().unwrap()),
+                    explode: flags & FLAG_EXPLODE != 0,
+                    restart: flags & FLAG_RESTART != 0,
+                    confirm: flags & FLAG_CONFIRM != 0,
+                    pause: flags & FLAG_PAUSE != 0,
+                    focus_lost: flags & FLAG_FOCUS_LOST != 0,
+                    zap: flags & FLAG_ZAP != 0,
+                    charge: flags & FLAG_CHARGE != 0,
+                    menu_up: flags & FLAG_MENU_UP != 0,
+                    menu_down: flags & FLAG_MENU_DOWN != 0,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            seed,
+            mode,
+            tuning,
+            waves,
+            frames,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+// Length-prefixed byte string, for the variable-size parts of the header
+fn write_blob(bytes: &mut Vec<u8>, blob: &[u8]) {
+    bytes.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(blob);
+}
+
+fn read_u32(bytes: &[u8], offset: &mut usize) -> Result<u32, ReplayError> {
+    let raw = bytes
+        .get(*offset..*offset + 4)
+        .ok_or(ReplayError::Truncated)?;
+    *offset += 4;
+    Ok(u32::from_le_bytes(raw.try_into().unwrap()))
+}
+
+fn read_blob<'a>(bytes: &'a [u8], offset: &mut usize) -> Result<&'a [u8], ReplayError> {
+    let len = read_u32(bytes, offset)? as usize;
+    let blob = bytes
+        .get(*offset..*offset + len)
+        .ok_or(ReplayError::Truncated)?;
+    *offset += len;
+    Ok(blob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_replay() -> Replay {
+        let tuning = Tuning {
+            energy_cost: 0.3,
+            difficulty: "hard".to_owned(),
+            ..Tuning::default()
+        };
+        let mut replay = Replay::new(0xDEAD_BEEF, GameMode::Zen, tuning, WaveScript::default());
+        replay.record(&FrameInput {
+            cursor_x: 12.5,
+            cursor_y: 600.0,
+            confirm: true,
+            menu_down: true,
+            ..FrameInput::default()
+        });
+        replay.record(&FrameInput {
+            cursor_x: -3.0,
+            cursor_y: 0.25,
+            explode: true,
+            charge: true,
+            zap: true,
+            ..FrameInput::default()
+        });
+        replay.record(&FrameInput {
+            restart: true,
+            pause: true,
+            focus_lost: true,
+            menu_up: true,
+            ..FrameInput::default()
+        });
+        replay
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let replay = sample_replay();
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+    }
+
+    #[test]
+    fn rejects_foreign_and_stale_files() {
+        let bytes = sample_replay().to_bytes();
+
+        let mut foreign = bytes.clone();
+        foreign[0] = b'X';
+        assert!(matches!(
+            Replay::from_bytes(&foreign),
+            Err(ReplayError::BadMagic)
+        ));
+
+        let mut stale = bytes.clone();
+        stale[4..6].copy_from_slice(&(REPLAY_VERSION - 1).to_le_bytes());
+        assert!(matches!(
+            Replay::from_bytes(&stale),
+            Err(ReplayError::UnsupportedVersion(_))
+        ));
+
+        let mut bad_mode = bytes;
+        bad_mode[18] = GameMode::ALL.len() as u8;
+        assert!(matches!(
+            Replay::from_bytes(&bad_mode),
+            Err(ReplayError::UnknownMode(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = sample_replay().to_bytes();
+        for len in [HEADER_LEN - 1, HEADER_LEN + 2, bytes.len() - 1] {
+            assert!(
+                matches!(
+                    Replay::from_bytes(&bytes[..len]),
+                    Err(ReplayError::Truncated)
+                ),
+                "accepted {} of {} bytes",
+                len,
+                bytes.len()
+            );
+        }
+    }
+}
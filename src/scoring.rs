@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+// Seconds after a scoring explosion to land another one and keep the combo
+pub const COMBO_WINDOW: f32 = 2.5;
+// Once the window lapses the multiplier drops one step this often
+const DECAY_STEP: f32 = 0.8;
+pub const MAX_MULTIPLIER: u32 = 8;
+// Bonus for destroying a boss, before the multiplier
+pub const BOSS_POINTS: u32 = 50;
+
+// Points for destroying `kills` popups with one explosion before the
+// multiplier: 1, 3, 6, 10, ... so packing a blast pays off
+pub fn multi_kill_points(kills: u32) -> u32 {
+    kills * (kills + 1) / 2
+}
+
+// Points for `kills` popups caught by a chain reaction link `depth` steps
+// away from the player's explosion, before the multiplier. Deeper links are
+// worth more.
+pub fn chain_points(kills: u32, depth: u32) -> u32 {
+    kills * (depth + 1)
+}
+
+// Tracks consecutive scoring explosions. Each one inside the window raises
+// the multiplier; letting the window run out bleeds it off step by step and
+// taking damage drops it straight back to x1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Combo {
+    pub streak: u32, // Scoring explosions in a row
+    pub timer: f32,  // Time left before the next decay step
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.streak).min(MAX_MULTIPLIER)
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.streak == 0 {
+            return;
+        }
+        self.timer -= dt;
+        if self.timer <= 0.0 {
+            self.streak -= 1;
+            self.timer = DECAY_STEP;
+        }
+    }
+
+    // Score an explosion at the current multiplier, then extend the streak.
+    // Explosions that kill nothing neither score nor extend it.
+    pub fn register_blast(&mut self, kills: u32) -> u32 {
+        if kills == 0 {
+            return 0;
+        }
+        let points = multi_kill_points(kills) * self.multiplier();
+        self.streak += 1;
+        self.timer = COMBO_WINDOW;
+        points
+    }
+
+    pub fn break_combo(&mut self) {
+        self.streak = 0;
+        self.timer = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blasts_inside_the_window_raise_the_multiplier() {
+        let mut combo = Combo::default();
+        assert_eq!(combo.register_blast(1), 1);
+        assert_eq!(combo.register_blast(2), 3 * 2);
+        assert_eq!(combo.multiplier(), 3);
+        // Misses leave the streak alone
+        assert_eq!(combo.register_blast(0), 0);
+        assert_eq!(combo.multiplier(), 3);
+    }
+
+    #[test]
+    fn multiplier_decays_one_step_at_a_time() {
+        let mut combo = Combo::default();
+        combo.register_blast(1);
+        combo.register_blast(1);
+        assert_eq!(combo.multiplier(), 3);
+
+        combo.update(COMBO_WINDOW - 0.1);
+        assert_eq!(combo.multiplier(), 3);
+        combo.update(0.2);
+        assert_eq!(combo.multiplier(), 2);
+        combo.update(DECAY_STEP - 0.1);
+        assert_eq!(combo.multiplier(), 2);
+        combo.update(0.2);
+        assert_eq!(combo.multiplier(), 1);
+        combo.update(10.0);
+        assert_eq!(combo.streak, 0);
+    }
+
+    #[test]
+    fn multiplier_is_capped_and_broken_by_damage() {
+        let mut combo = Combo::default();
+        for _ in 0..20 {
+            combo.register_blast(1);
+        }
+        assert_eq!(combo.multiplier(), MAX_MULTIPLIER);
+        combo.break_combo();
+        assert_eq!(combo.multiplier(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::game::{GameState, Scene};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Default quick save location, relative to the working directory
+pub const SNAPSHOT_PATH: &str = "savegame.json";
+
+// Bump when GameState changes in a way old saves cannot be read into
+pub const SNAPSHOT_VERSION: u32 = 12;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Invalid(String), // Parses, but the settings inside would break the game
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "save I/O error: {}", err),
+            SnapshotError::Json(err) => write!(f, "save format error: {}", err),
+            SnapshotError::UnsupportedVersion(v) => write!(f, "unsupported save version {}", v),
+            SnapshotError::Invalid(msg) => write!(f, "invalid save: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::Json(err)
+    }
+}
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    state: &'a GameState,
+}
+
+// Read on its own first so an old save reports its version instead of a
+// confusing field error
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    state: GameState,
+}
+
+pub fn to_json(game: &GameState) -> Result<String, SnapshotError> {
+    Ok(serde_json::to_string_pretty(&SnapshotRef {
+        version: SNAPSHOT_VERSION,
+        state: game,
+    })?)
+}
+
+// A run saved mid-play comes back paused so the player is not ambushed
+pub fn from_json(text: &str) -> Result<GameState, SnapshotError> {
+    let header: SnapshotHeader = serde_json::from_str(text)?;
+    if header.version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(header.version));
+    }
+
+    let snapshot: Snapshot = serde_json::from_str(text)?;
+    let mut state = snapshot.state;
+    // Saves double as hand-written scenarios, so check the settings the same
+    // way their own files are checked
+    state
+        .tuning
+        .validate()
+        .map_err(|err| SnapshotError::Invalid(err.to_string()))?;
+    state
+        .wave_script
+        .validate()
+        .map_err(|err| SnapshotError::Invalid(format!("wave script: {}", err)))?;
+    state
+        .difficulty
+        .validate()
+        .map_err(|err| SnapshotError::Invalid(format!("difficulty: {}", err)))?;
+    if state.scene == Scene::Playing {
+        state.scene = Scene::Paused;
+    }
+    Ok(state)
+}
+
+pub fn save(game: &GameState, path: &Path) -> Result<(), SnapshotError> {
+    fs::write(path, to_json(game)?)?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<GameState, SnapshotError> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuning::Tuning;
+
+    fn saved_run() -> GameState {
+        let mut game = GameState::new(5, Tuning::default());
+        game.start_run();
+        game.score = 17;
+        game
+    }
+
+    // Round-trip a save through JSON after editing it like a scenario author
+    fn edited(edit: impl FnOnce(&mut serde_json::Value)) -> Result<GameState, SnapshotError> {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&to_json(&saved_run()).unwrap()).unwrap();
+        edit(&mut json["state"]);
+        from_json(&json.to_string())
+    }
+
+    #[test]
+    fn round_trip_resumes_paused() {
+        let state = from_json(&to_json(&saved_run()).unwrap()).unwrap();
+        assert_eq!(state.score, 17);
+        assert_eq!(state.scene, Scene::Paused);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let text = to_json(&saved_run())
+            .unwrap()
+            .replacen(&SNAPSHOT_VERSION.to_string(), "1", 1);
+        assert!(matches!(
+            from_json(&text),
+            Err(SnapshotError::UnsupportedVersion(1))
+        ));
+    }
+
+    #[test]
+    fn rejects_settings_that_would_break_the_game() {
+        let broken: [fn(&mut serde_json::Value); 4] = [
+            |state| state["difficulty"]["keys"] = serde_json::json!([]),
+            |state| state["wave_script"]["loop_from"] = serde_json::json!(99),
+            |state| state["tuning"]["charge_time"] = serde_json::json!(0.0),
+            |state| state["tuning"]["explosion_radius"] = serde_json::json!(0.0),
+        ];
+        for edit in broken {
+            assert!(matches!(edited(edit), Err(SnapshotError::Invalid(_))));
+        }
+        assert!(edited(|state| state["tuning"]["energy_cost"] = serde_json::json!(0.5)).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::rng::GameRng;
 use std::f32::consts::PI;
 
 pub struct WavGenerator;
@@ -29,7 +30,36 @@ impl WavGenerator {
         Self::create_wav_from_samples(&samples, sample_rate)
     }
 
-    pub fn generate_noise(duration: f32, volume: f32) -> Vec<u8> {
+    // Tone gliding linearly from `start_freq` to `end_freq`
+    pub fn generate_sweep(start_freq: f32, end_freq: f32, duration: f32, volume: f32) -> Vec<u8> {
+        let sample_rate = 44100;
+        let num_samples = (sample_rate as f32 * duration) as usize;
+        let mut samples = Vec::with_capacity(num_samples);
+        let mut phase: f32 = 0.0;
+
+        for i in 0..num_samples {
+            let time = i as f32 / sample_rate as f32;
+            let frequency = start_freq + (end_freq - start_freq) * time / duration;
+            // Accumulate phase so the pitch change stays smooth
+            phase = (phase + 2.0 * PI * frequency / sample_rate as f32) % (2.0 * PI);
+
+            let fade_time = 0.011;
+            let envelope = if time < fade_time {
+                time / fade_time
+            } else if time > duration - fade_time {
+                (duration - time) / fade_time
+            } else {
+                1.0
+            };
+
+            let sample = (32767.0 * volume * envelope * phase.sin()) as i16;
+            samples.push(sample);
+        }
+
+        Self::create_wav_from_samples(&samples, sample_rate)
+    }
+
+    pub fn generate_noise(duration: f32, volume: f32, rng: &mut GameRng) -> Vec<u8> {
         let sample_rate = 44100;
         let num_samples = (sample_rate as f32 * duration) as usize;
         let mut samples = Vec::with_capacity(num_samples);
@@ -45,8 +75,7 @@ impl WavGenerator {
                 1.0
             };
 
-            let sample =
-                (32767.0 * volume * envelope * (macroquad::rand::gen_range(-1.0, 1.0))) as i16;
+            let sample = (32767.0 * volume * envelope * rng.gen_range_f32(-1.0, 1.0)) as i16;
             samples.push(sample);
         }
 
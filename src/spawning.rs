@@ -0,0 +1,234 @@
+use crate::game::{VIRTUAL_H, VIRTUAL_W};
+use crate::popup::Popup;
+use crate::rng::GameRng;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Radius popups of a cluster or corner burst are scattered within
+pub const CLUSTER_SPREAD: f32 = 70.0;
+// Smallest radius of a ring around the cursor; grows to the safe distance
+const RING_RADIUS: f32 = 220.0;
+// Tries at finding a random spot far enough from the cursor
+const PLACEMENT_ATTEMPTS: usize = 12;
+
+// Arena edges and corners, numbered clockwise from the top / top-left
+pub const SIDES: u8 = 4;
+
+// Where a new popup goes. The wave director picks the placement; the game
+// resolves it against the cursor when the popup is created.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Placement {
+    Anywhere,                  // Random spot inside the arena
+    Near(f32, f32),            // Around a point (cluster groups)
+    Edge,                      // Just outside a random edge, sliding in
+    Ring { angle: f32 },       // On a circle around the cursor
+    Line { side: u8, t: f32 }, // Outside one edge, `t` 0..1 along it
+    Corner { corner: u8 },     // Bunched into one corner of the arena
+}
+
+// Position a freshly created popup. Nothing ends up within `safe_distance`
+// of the cursor; patterns tied to a side or corner switch to the opposite
+// one if the cursor is sitting there.
+pub fn place(
+    popup: &mut Popup,
+    placement: Placement,
+    cursor: Vec2,
+    safe_distance: f32,
+    rng: &mut GameRng,
+) {
+    let half = vec2(popup.w, popup.h) / 2.0;
+    let center = match placement {
+        Placement::Anywhere => {
+            let mut best = random_inside(half, rng);
+            for _ in 1..PLACEMENT_ATTEMPTS {
+                if best.distance(cursor) >= safe_distance {
+                    break;
+                }
+                let candidate = random_inside(half, rng);
+                if candidate.distance(cursor) > best.distance(cursor) {
+                    best = candidate;
+                }
+            }
+            keep_away(best, cursor, safe_distance)
+        }
+        Placement::Near(x, y) => near_point(vec2(x, y), half, cursor, safe_distance, rng),
+        Placement::Edge => edge_point(half, cursor, safe_distance, rng),
+        Placement::Ring { angle } => {
+            let radius = RING_RADIUS.max(safe_distance);
+            cursor + vec2(angle.cos(), angle.sin()) * radius
+        }
+        Placement::Line { side, t } => {
+            let side = if distance_to_side(side, cursor) < safe_distance {
+                opposite(side)
+            } else {
+                side
+            };
+            point_on_side(side, t, half)
+        }
+        Placement::Corner { corner } => {
+            let mut anchor = corner_point(corner);
+            if anchor.distance(cursor) < safe_distance + CLUSTER_SPREAD {
+                anchor = corner_point(opposite(corner));
+            }
+            let angle = rng.gen_range_f32(0.0, std::f32::consts::TAU);
+            let dist = rng.gen_range_f32(0.0, CLUSTER_SPREAD);
+            let spot = anchor + vec2(angle.cos(), angle.sin()) * dist;
+            spot.clamp(half, vec2(VIRTUAL_W, VIRTUAL_H) - half)
+        }
+    };
+    popup.move_center_to(center.x, center.y);
+}
+
+// Around `pos` but inside the arena and clear of the cursor. Pushing away
+// from the cursor can go through a wall, and pulling the spot back inside
+// can bring it close again, so the result is checked after clamping; if
+// it is still too close the spot is mirrored to the other side of the
+// cursor, and failing that the popup comes in from an edge instead.
+fn near_point(pos: Vec2, half: Vec2, cursor: Vec2, safe_distance: f32, rng: &mut GameRng) -> Vec2 {
+    let max = vec2(VIRTUAL_W, VIRTUAL_H) - half;
+    let pushed = keep_away(pos, cursor, safe_distance);
+    let mirrored = cursor * 2.0 - pushed;
+    for spot in [pushed, mirrored] {
+        let spot = spot.clamp(half, max);
+        if spot.distance(cursor) >= safe_distance {
+            return spot;
+        }
+    }
+    edge_point(half, cursor, safe_distance, rng)
+}
+
+// Just outside a random edge, or the opposite one if the cursor is there
+fn edge_point(half: Vec2, cursor: Vec2, safe_distance: f32, rng: &mut GameRng) -> Vec2 {
+    let mut side = rng.gen_range_usize(0, SIDES as usize) as u8;
+    let t = rng.gen_range_f32(0.0, 1.0);
+    if point_on_side(side, t, half).distance(cursor) < safe_distance {
+        side = opposite(side);
+    }
+    point_on_side(side, t, half)
+}
+
+fn random_inside(half: Vec2, rng: &mut GameRng) -> Vec2 {
+    vec2(
+        rng.gen_range_f32(half.x, VIRTUAL_W - half.x),
+        rng.gen_range_f32(half.y, VIRTUAL_H - half.y),
+    )
+}
+
+// Push `pos` straight away from the cursor until it is `safe_distance` off
+fn keep_away(pos: Vec2, cursor: Vec2, safe_distance: f32) -> Vec2 {
+    let offset = pos - cursor;
+    if offset.length() >= safe_distance {
+        return pos;
+    }
+    let away = offset.try_normalize().unwrap_or(Vec2::X);
+    cursor + away * safe_distance
+}
+
+fn opposite(side: u8) -> u8 {
+    (side + 2) % SIDES
+}
+
+// Centre for a popup just outside `side`, `t` 0..1 along it
+fn point_on_side(side: u8, t: f32, half: Vec2) -> Vec2 {
+    match side % SIDES {
+        0 => vec2(t * VIRTUAL_W, -half.y),
+        1 => vec2(VIRTUAL_W + half.x, t * VIRTUAL_H),
+        2 => vec2((1.0 - t) * VIRTUAL_W, VIRTUAL_H + half.y),
+        _ => vec2(-half.x, (1.0 - t) * VIRTUAL_H),
+    }
+}
+
+fn distance_to_side(side: u8, p: Vec2) -> f32 {
+    match side % SIDES {
+        0 => p.y,
+        1 => VIRTUAL_W - p.x,
+        2 => VIRTUAL_H - p.y,
+        _ => p.x,
+    }
+}
+
+fn corner_point(corner: u8) -> Vec2 {
+    let inset = CLUSTER_SPREAD;
+    match corner % SIDES {
+        0 => vec2(inset, inset),
+        1 => vec2(VIRTUAL_W - inset, inset),
+        2 => vec2(VIRTUAL_W - inset, VIRTUAL_H - inset),
+        _ => vec2(inset, VIRTUAL_H - inset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::BehaviorKind;
+    use crate::difficulty::DifficultyLevel;
+    use crate::tuning::Tuning;
+
+    const SAFE: f32 = 150.0;
+
+    // Cursor positions touching each wall and sitting in each corner
+    fn cursors_at_walls() -> Vec<Vec2> {
+        vec![
+            vec2(0.0, 300.0),
+            vec2(60.0, 300.0),
+            vec2(VIRTUAL_W, 300.0),
+            vec2(400.0, 0.0),
+            vec2(400.0, VIRTUAL_H),
+            vec2(0.0, 0.0),
+            vec2(VIRTUAL_W, VIRTUAL_H),
+        ]
+    }
+
+    fn placed(placement: Placement, cursor: Vec2, rng: &mut GameRng) -> Vec2 {
+        let mut popup = Popup::new(
+            VIRTUAL_W,
+            VIRTUAL_H,
+            BehaviorKind::Chaser,
+            &Tuning::default(),
+            &DifficultyLevel::default(),
+            rng,
+        );
+        place(&mut popup, placement, cursor, SAFE, rng);
+        vec2(popup.x + popup.w / 2.0, popup.y + popup.h / 2.0)
+    }
+
+    #[test]
+    fn near_a_point_between_the_cursor_and_a_wall_stays_clear() {
+        let mut rng = GameRng::new(1);
+        let cursor = vec2(60.0, 300.0);
+        let center = placed(Placement::Near(30.0, 300.0), cursor, &mut rng);
+        assert!(center.distance(cursor) >= SAFE, "placed at {}", center);
+        assert!(center.x > 0.0 && center.x < VIRTUAL_W);
+    }
+
+    #[test]
+    fn every_placement_keeps_clear_of_a_cursor_at_the_walls() {
+        let mut rng = GameRng::new(2);
+        for cursor in cursors_at_walls() {
+            for i in 0..50 {
+                let t = i as f32 / 50.0;
+                let near = cursor + vec2(t * 80.0 - 40.0, 40.0 - t * 80.0);
+                for placement in [
+                    Placement::Anywhere,
+                    Placement::Near(near.x, near.y),
+                    Placement::Near(cursor.x, cursor.y),
+                    Placement::Edge,
+                    Placement::Ring {
+                        angle: t * std::f32::consts::TAU,
+                    },
+                    Placement::Line { side: i as u8, t },
+                    Placement::Corner { corner: i as u8 },
+                ] {
+                    let center = placed(placement, cursor, &mut rng);
+                    assert!(
+                        center.distance(cursor) >= SAFE - 1e-3,
+                        "{:?} with the cursor at {} placed at {}",
+                        placement,
+                        cursor,
+                        center
+                    );
+                }
+            }
+        }
+    }
+}
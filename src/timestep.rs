@@ -0,0 +1,43 @@
+// Fixed-step simulation clock. The game always advances in SIM_DT steps no
+// matter the display rate; leftover time is exposed as an interpolation
+// factor so rendering can blend between the last two simulation states.
+
+pub const SIM_HZ: f32 = 120.0;
+pub const SIM_DT: f32 = 1.0 / SIM_HZ;
+
+// Longest frame we try to catch up on. Anything beyond this (window drag,
+// debugger break) is dropped instead of fast-forwarding the game.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+pub struct FixedTimestep {
+    accumulator: f32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: 0.0 }
+    }
+
+    // Add a frame's worth of real time and return how many simulation steps to run
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.clamp(0.0, MAX_FRAME_TIME);
+
+        let mut steps = 0;
+        while self.accumulator >= SIM_DT {
+            self.accumulator -= SIM_DT;
+            steps += 1;
+        }
+        steps
+    }
+
+    // How far (0..1) we are between the previous and the current simulation step
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / SIM_DT
+    }
+}
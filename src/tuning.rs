@@ -0,0 +1,335 @@
+use crate::difficulty::PRESET_NAMES;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Default location of the tuning file, relative to the working directory
+pub const TUNING_PATH: &str = "tuning.toml";
+
+// Gameplay numbers designers tweak. Every field is optional in the file;
+// anything missing falls back to the compiled-in default below.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    pub spawn_interval: f32,
+    pub spawn_safe_distance: f32,
+    pub telegraph_time: f32,
+    pub explosion_radius: f32,
+    pub energy_cost: f32,
+    pub zap_cost: f32,
+    pub charge_time: f32,
+    pub charge_radius_scale: f32,
+    pub charge_cost_scale: f32,
+    pub energy_regen: f32,
+    pub damage_rate: f32,
+    pub pulse_hitboxes: bool,
+    pub popup_size_min: f32,
+    pub popup_size_max: f32,
+    pub popup_speed_min: f32,
+    pub popup_speed_max: f32,
+    pub popup_hp_min: f32,
+    pub popup_hp_max: f32,
+    pub explosion_damage: f32,
+    pub damage_falloff: f32,
+    pub chain_radius: f32,
+    pub chain_damage: f32,
+    pub chain_delay: f32,
+    pub chain_max_depth: u32,
+    pub pickup_drop_chance: f32,
+    pub boss_hp: f32,
+    pub separation_strength: f32,
+    pub separation_spacing: f32,
+    pub alignment: f32,
+    pub difficulty: String, // Name of a built-in difficulty curve
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 1.2,
+            spawn_safe_distance: 150.0,
+            telegraph_time: 0.8,
+            explosion_radius: 90.0,
+            energy_cost: 0.2,
+            zap_cost: 0.05,
+            charge_time: 1.2,
+            charge_radius_scale: 2.0,
+            charge_cost_scale: 2.5,
+            energy_regen: 0.1,
+            damage_rate: 0.15,
+            pulse_hitboxes: true,
+            popup_size_min: 40.0,
+            popup_size_max: 60.0,
+            popup_speed_min: 40.0,
+            popup_speed_max: 110.0,
+            popup_hp_min: 0.5,
+            popup_hp_max: 1.2,
+            explosion_damage: 1.0,
+            damage_falloff: 0.3,
+            chain_radius: 40.0,
+            chain_damage: 0.5,
+            chain_delay: 0.25,
+            chain_max_depth: 2,
+            pickup_drop_chance: 0.08,
+            boss_hp: 8.0,
+            separation_strength: 160.0,
+            separation_spacing: 0.9,
+            alignment: 0.0,
+            difficulty: "normal".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TuningError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuningError::Io(err) => write!(f, "tuning I/O error: {}", err),
+            TuningError::Parse(err) => write!(f, "tuning parse error: {}", err),
+            TuningError::Invalid(msg) => write!(f, "invalid tuning: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TuningError {}
+
+impl From<io::Error> for TuningError {
+    fn from(err: io::Error) -> Self {
+        TuningError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for TuningError {
+    fn from(err: toml::de::Error) -> Self {
+        TuningError::Parse(err)
+    }
+}
+
+impl Tuning {
+    pub fn from_toml(text: &str) -> Result<Self, TuningError> {
+        let tuning: Tuning = toml::from_str(text)?;
+        tuning.validate()?;
+        Ok(tuning)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TuningError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    // Load the file if it exists, otherwise use the defaults. A broken file
+    // is reported and ignored rather than stopping the game.
+    pub fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(tuning) => tuning,
+            Err(TuningError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                eprintln!("{}: {}, using defaults", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), TuningError> {
+        if self.spawn_interval <= 0.0 {
+            return Err(TuningError::Invalid(
+                "spawn_interval must be positive".to_owned(),
+            ));
+        }
+        if self.spawn_safe_distance < 0.0 || self.telegraph_time < 0.0 {
+            return Err(TuningError::Invalid(
+                "spawn_safe_distance and telegraph_time must be non-negative".to_owned(),
+            ));
+        }
+        if self.popup_size_min <= 0.0 || self.popup_size_min > self.popup_size_max {
+            return Err(TuningError::Invalid(
+                "popup_size_min must be positive and not above popup_size_max".to_owned(),
+            ));
+        }
+        if self.popup_speed_min < 0.0 || self.popup_speed_min > self.popup_speed_max {
+            return Err(TuningError::Invalid(
+                "popup_speed_min must be non-negative and not above popup_speed_max".to_owned(),
+            ));
+        }
+        if self.zap_cost < 0.0 {
+            return Err(TuningError::Invalid(
+                "zap_cost must be non-negative".to_owned(),
+            ));
+        }
+        if self.charge_time <= 0.0 {
+            return Err(TuningError::Invalid(
+                "charge_time must be positive".to_owned(),
+            ));
+        }
+        if self.charge_radius_scale < 1.0 || self.charge_cost_scale < 1.0 {
+            return Err(TuningError::Invalid(
+                "charge_radius_scale and charge_cost_scale must be at least 1".to_owned(),
+            ));
+        }
+        if self.popup_hp_min <= 0.0 || self.popup_hp_min > self.popup_hp_max {
+            return Err(TuningError::Invalid(
+                "popup_hp_min must be positive and not above popup_hp_max".to_owned(),
+            ));
+        }
+        if self.explosion_damage <= 0.0 {
+            return Err(TuningError::Invalid(
+                "explosion_damage must be positive".to_owned(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.damage_falloff) {
+            return Err(TuningError::Invalid(
+                "damage_falloff must be between 0 and 1".to_owned(),
+            ));
+        }
+        if self.explosion_radius <= 0.0 || self.chain_radius <= 0.0 {
+            return Err(TuningError::Invalid(
+                "explosion_radius and chain_radius must be positive".to_owned(),
+            ));
+        }
+        if self.chain_damage < 0.0 || self.chain_delay < 0.0 {
+            return Err(TuningError::Invalid(
+                "chain_damage and chain_delay must be non-negative".to_owned(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.pickup_drop_chance) {
+            return Err(TuningError::Invalid(
+                "pickup_drop_chance must be between 0 and 1".to_owned(),
+            ));
+        }
+        if self.boss_hp <= 0.0 {
+            return Err(TuningError::Invalid("boss_hp must be positive".to_owned()));
+        }
+        if self.separation_strength < 0.0 || self.separation_spacing < 0.0 {
+            return Err(TuningError::Invalid(
+                "separation_strength and separation_spacing must be non-negative".to_owned(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.alignment) {
+            return Err(TuningError::Invalid(
+                "alignment must be between 0 and 1".to_owned(),
+            ));
+        }
+        if !PRESET_NAMES.contains(&self.difficulty.as_str()) {
+            return Err(TuningError::Invalid(format!(
+                "unknown difficulty '{}', expected one of {}",
+                self.difficulty,
+                PRESET_NAMES.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Polls a tuning file's modification time and reloads it when it changes
+pub struct TuningWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+    poll_timer: f32,
+}
+
+impl TuningWatcher {
+    // Seconds between file system checks
+    const POLL_INTERVAL: f32 = 0.5;
+
+    pub fn new(path: PathBuf) -> Self {
+        let last_modified = Self::modified(&path);
+        Self {
+            path,
+            last_modified,
+            poll_timer: 0.0,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    // Returns freshly loaded values when the file changed since the last check
+    pub fn poll(&mut self, dt: f32) -> Option<Tuning> {
+        self.poll_timer += dt;
+        if self.poll_timer < Self::POLL_INTERVAL {
+            return None;
+        }
+        self.poll_timer = 0.0;
+
+        let modified = Self::modified(&self.path);
+        if modified.is_none() || modified == self.last_modified {
+            return None;
+        }
+        self.last_modified = modified;
+
+        match Tuning::load(&self.path) {
+            Ok(tuning) => Some(tuning),
+            Err(err) => {
+                eprintln!("{}: {}, keeping previous values", self.path.display(), err);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_gives_the_defaults() {
+        assert_eq!(Tuning::from_toml("").unwrap(), Tuning::default());
+    }
+
+    #[test]
+    fn shipped_tuning_file_is_valid() {
+        Tuning::load(Path::new(TUNING_PATH)).unwrap();
+    }
+
+    #[test]
+    fn missing_fields_keep_their_defaults() {
+        let tuning = Tuning::from_toml("energy_cost = 0.5\ndifficulty = \"hard\"").unwrap();
+        assert_eq!(tuning.energy_cost, 0.5);
+        assert_eq!(tuning.difficulty, "hard");
+        assert_eq!(tuning.explosion_radius, Tuning::default().explosion_radius);
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        for text in [
+            "spawn_interval = 0.0",
+            "popup_size_min = 50.0\npopup_size_max = 40.0",
+            "damage_falloff = 1.5",
+            "explosion_radius = 0.0",
+            "chain_radius = 0.0",
+            "pickup_drop_chance = -0.1",
+            "difficulty = \"nightmare\"",
+        ] {
+            assert!(
+                matches!(Tuning::from_toml(text), Err(TuningError::Invalid(_))),
+                "accepted {:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert!(matches!(
+            Tuning::from_toml("energy_cost = "),
+            Err(TuningError::Parse(_))
+        ));
+        assert!(matches!(
+            Tuning::from_toml("energy_cost = \"lots\""),
+            Err(TuningError::Parse(_))
+        ));
+    }
+}
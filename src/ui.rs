@@ -1,10 +1,13 @@
 use crate::assets::GameAssets;
 use crate::game::{VIRTUAL_H, VIRTUAL_W};
+use crate::modes::{GameMode, TIME_ATTACK_WARNING};
+use crate::pickups::PickupKind;
 use macroquad::prelude::*;
 
 pub struct UI;
 
 impl UI {
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_bar(
         x: f32,
         y: f32,
@@ -65,8 +68,11 @@ impl UI {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_score_and_popups(
         score: u32,
+        multiplier: u32,
+        combo: u32,
         popup_count: usize,
         scale: f32,
         offset_x: f32,
@@ -74,7 +80,7 @@ impl UI {
         assets: &GameAssets,
     ) {
         draw_text_ex(
-            &format!("Score: {}", score),
+            format!("Score: {}", score),
             offset_x + 20.0 * scale,
             offset_y + 30.0 * scale,
             TextParams {
@@ -85,8 +91,23 @@ impl UI {
             },
         );
 
+        // Only shown while a combo is running
+        if combo > 0 {
+            draw_text_ex(
+                format!("x{}  COMBO {}", multiplier, combo),
+                offset_x + 20.0 * scale,
+                offset_y + 80.0 * scale,
+                TextParams {
+                    font: Some(&assets.font),
+                    font_size: (20.0 * scale) as u16,
+                    color: ORANGE,
+                    ..Default::default()
+                },
+            );
+        }
+
         draw_text_ex(
-            &format!("Popups: {}", popup_count),
+            format!("Popups: {}", popup_count),
             offset_x + 20.0 * scale,
             offset_y + 55.0 * scale,
             TextParams {
@@ -98,8 +119,15 @@ impl UI {
         );
     }
 
-    pub fn draw_game_over(scale: f32, offset_x: f32, offset_y: f32, assets: &GameAssets) {
-        let go_txt = "GAME OVER";
+    pub fn draw_game_over(
+        headline: &str,
+        color: Color,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let go_txt = headline;
         let font_size = (60.0 * scale) as u16;
         let text_size = measure_text(go_txt, Some(&assets.font), font_size, 1.0);
 
@@ -110,12 +138,12 @@ impl UI {
             TextParams {
                 font: Some(&assets.font),
                 font_size,
-                color: RED,
+                color,
                 ..Default::default()
             },
         );
 
-        let restart_txt = "PRESS 'R' TO RESTART";
+        let restart_txt = "PRESS 'R' TO RESTART - ESC FOR MENU";
         let restart_size = (24.0 * scale) as u16;
         let restart_text_size = measure_text(restart_txt, Some(&assets.font), restart_size, 1.0);
 
@@ -131,4 +159,288 @@ impl UI {
             },
         );
     }
+
+    // Text centred horizontally in the virtual area; `y` is in virtual units
+    #[allow(clippy::too_many_arguments)]
+    fn draw_centered_text(
+        text: &str,
+        y: f32,
+        size: f32,
+        color: Color,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let font_size = (size * scale) as u16;
+        let text_size = measure_text(text, Some(&assets.font), font_size, 1.0);
+
+        draw_text_ex(
+            text,
+            offset_x + (VIRTUAL_W * scale - text_size.width) / 2.0,
+            offset_y + y * scale,
+            TextParams {
+                font: Some(&assets.font),
+                font_size,
+                color,
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn draw_title(
+        selected: GameMode,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let center_y = VIRTUAL_H / 2.0;
+        Self::draw_centered_text(
+            "CURSOR CRISIS",
+            center_y - 130.0,
+            64.0,
+            YELLOW,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+
+        // Mode list with the selected entry marked
+        for (i, mode) in GameMode::ALL.iter().enumerate() {
+            let (text, color) = if *mode == selected {
+                (format!("> {} <", mode.label()), mode.color())
+            } else {
+                (mode.label().to_owned(), DARKGRAY)
+            };
+            Self::draw_centered_text(
+                &text,
+                center_y - 70.0 + i as f32 * 28.0,
+                24.0,
+                color,
+                scale,
+                offset_x,
+                offset_y,
+                assets,
+            );
+        }
+        Self::draw_centered_text(
+            selected.description(),
+            center_y + 65.0,
+            16.0,
+            GRAY,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+
+        Self::draw_centered_text(
+            "UP/DOWN TO PICK A MODE - CLICK OR ENTER TO START",
+            center_y + 110.0,
+            22.0,
+            WHITE,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+        Self::draw_centered_text(
+            "LEFT CLICK ZAP - RIGHT CLICK EXPLODE, HOLD TO CHARGE - ESC PAUSE",
+            center_y + 150.0,
+            16.0,
+            GRAY,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+        Self::draw_centered_text(
+            "F3 HITBOXES - F5 TO SAVE - F9 TO LOAD",
+            center_y + 175.0,
+            16.0,
+            GRAY,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+    }
+
+    pub fn draw_paused(scale: f32, offset_x: f32, offset_y: f32, assets: &GameAssets) {
+        // Dim the frozen game behind the overlay
+        draw_rectangle(
+            offset_x,
+            offset_y,
+            VIRTUAL_W * scale,
+            VIRTUAL_H * scale,
+            Color::new(0.0, 0.0, 0.0, 0.6),
+        );
+
+        Self::draw_centered_text(
+            "PAUSED",
+            VIRTUAL_H / 2.0,
+            60.0,
+            WHITE,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+        Self::draw_centered_text(
+            "PRESS ESC TO RESUME - R TO QUIT TO MENU",
+            VIRTUAL_H / 2.0 + 50.0,
+            22.0,
+            GRAY,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+    }
+
+    // `progress` runs 0..1 over the banner's lifetime; it fades in and out
+    pub fn draw_wave_banner(
+        wave_number: u32,
+        boss: bool,
+        progress: f32,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let alpha = (progress.min(1.0 - progress) * 4.0).clamp(0.0, 1.0);
+        Self::draw_centered_text(
+            &if boss {
+                format!("WAVE {} - BOSS", wave_number)
+            } else {
+                format!("WAVE {}", wave_number)
+            },
+            VIRTUAL_H / 2.0 - 60.0,
+            56.0,
+            Color::new(1.0, 0.9, 0.4, alpha),
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+    }
+
+    // Stands in for the health bar in modes without one; (x, y) is the
+    // baseline in screen space
+    pub fn draw_mode_badge(mode: GameMode, x: f32, y: f32, scale: f32, assets: &GameAssets) {
+        draw_text_ex(
+            mode.label(),
+            x,
+            y,
+            TextParams {
+                font: Some(&assets.font),
+                font_size: (24.0 * scale) as u16,
+                color: mode.color(),
+                ..Default::default()
+            },
+        );
+    }
+
+    // Time attack clock as m:ss, centred at `y` in virtual space
+    pub fn draw_countdown(
+        seconds_left: f32,
+        y: f32,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let whole = seconds_left.ceil() as u32;
+        let color = if seconds_left <= TIME_ATTACK_WARNING {
+            RED
+        } else {
+            WHITE
+        };
+        Self::draw_centered_text(
+            &format!("{}:{:02}", whole / 60, whole % 60),
+            y,
+            32.0,
+            color,
+            scale,
+            offset_x,
+            offset_y,
+            assets,
+        );
+    }
+
+    // One line per active power-up, stacked down from (x, y) in screen space
+    pub fn draw_effect_timers(
+        timers: &[(PickupKind, f32)],
+        x: f32,
+        y: f32,
+        scale: f32,
+        assets: &GameAssets,
+    ) {
+        for (i, (kind, left)) in timers.iter().enumerate() {
+            draw_text_ex(
+                format!("{} {:.1}s", kind.label(), left),
+                x,
+                y + i as f32 * 24.0 * scale,
+                TextParams {
+                    font: Some(&assets.font),
+                    font_size: (18.0 * scale) as u16,
+                    color: kind.color(),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    // Centred on (x, y) in virtual space
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_floating_text(
+        text: &str,
+        x: f32,
+        y: f32,
+        alpha: f32,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let font_size = (20.0 * scale) as u16;
+        let text_size = measure_text(text, Some(&assets.font), font_size, 1.0);
+        draw_text_ex(
+            text,
+            offset_x + x * scale - text_size.width / 2.0,
+            offset_y + y * scale,
+            TextParams {
+                font: Some(&assets.font),
+                font_size,
+                color: Color::new(1.0, 0.85, 0.3, alpha.clamp(0.0, 1.0)),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn draw_replay_label(
+        finished: bool,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        assets: &GameAssets,
+    ) {
+        let label = if finished { "REPLAY ENDED" } else { "REPLAY" };
+        let font_size = (20.0 * scale) as u16;
+        let text_size = measure_text(label, Some(&assets.font), font_size, 1.0);
+
+        draw_text_ex(
+            label,
+            offset_x + (VIRTUAL_W * scale - text_size.width) / 2.0,
+            offset_y + 30.0 * scale,
+            TextParams {
+                font: Some(&assets.font),
+                font_size,
+                color: YELLOW,
+                ..Default::default()
+            },
+        );
+    }
 }
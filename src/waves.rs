@@ -0,0 +1,310 @@
+use crate::behavior::BehaviorKind;
+use crate::difficulty::DifficultyLevel;
+use crate::game::{VIRTUAL_H, VIRTUAL_W};
+use crate::popup::BodyShape;
+use crate::rng::GameRng;
+use crate::spawning::{CLUSTER_SPREAD, Placement, SIDES};
+use crate::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const DEFAULT_SCRIPT: &str = include_str!("../assets/waves/default.toml");
+
+// How long the "WAVE N" banner stays up before the wave starts spawning
+pub const BANNER_TIME: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnPattern {
+    #[default]
+    Random, // Anywhere in the arena
+    Cluster, // Bunched around one random point
+    Edge,    // Sliding in from outside a random edge
+    Ring,    // Evenly spaced on a circle around the cursor
+    Line,    // A wall sweeping in from one edge
+    Corner,  // Bursting out of one corner
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpawnGroup {
+    pub count: u32,
+    #[serde(default)]
+    pub delay: f32,
+    pub interval: Option<f32>, // Falls back to `Tuning::spawn_interval`
+    #[serde(default)]
+    pub shape: BodyShape,
+    pub behavior: Option<BehaviorKind>, // Random kind matching `shape` when unset
+    #[serde(default)]
+    pub pattern: SpawnPattern,
+}
+
+fn default_timeout() -> f32 {
+    10.0
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveDef {
+    pub groups: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub breather: f32,
+    #[serde(default = "default_timeout")]
+    pub timeout: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveScript {
+    #[serde(default)]
+    pub loop_from: usize,
+    #[serde(default)]
+    pub boss_every: u32, // A boss joins every Nth wave; 0 means never
+    pub waves: Vec<WaveDef>,
+}
+
+impl Default for WaveScript {
+    fn default() -> Self {
+        Self::from_toml(DEFAULT_SCRIPT).expect("built-in wave script is valid")
+    }
+}
+
+impl WaveScript {
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let script: WaveScript = toml::from_str(text).map_err(|err| err.to_string())?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        Self::from_toml(&text)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.waves.is_empty() {
+            return Err("a wave script needs at least one wave".to_owned());
+        }
+        if self.loop_from >= self.waves.len() {
+            return Err("loop_from must point at an existing wave".to_owned());
+        }
+        for (i, wave) in self.waves.iter().enumerate() {
+            if wave.groups.iter().all(|g| g.count == 0) {
+                return Err(format!("wave {} spawns nothing", i + 1));
+            }
+            if wave
+                .groups
+                .iter()
+                .any(|g| g.interval.is_some_and(|t| t < 0.0))
+            {
+                return Err(format!("wave {} has a negative interval", i + 1));
+            }
+            if wave.groups.iter().any(|g| {
+                g.shape != BodyShape::Any && g.behavior.is_some_and(|b| b.shape() != g.shape)
+            }) {
+                return Err(format!(
+                    "wave {} asks for a behavior in the wrong shape",
+                    i + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_boss_wave(&self, number: u32) -> bool {
+        self.boss_every > 0 && number.is_multiple_of(self.boss_every)
+    }
+
+    // Definition for a 1-based wave number, looping once the script runs out
+    pub fn wave(&self, number: u32) -> &WaveDef {
+        let index = number.saturating_sub(1) as usize;
+        if index < self.waves.len() {
+            return &self.waves[index];
+        }
+        let loop_len = self.waves.len() - self.loop_from;
+        &self.waves[self.loop_from + (index - self.waves.len()) % loop_len]
+    }
+}
+
+// A popup the director wants spawned this step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnRequest {
+    pub shape: BodyShape,
+    pub behavior: Option<BehaviorKind>,
+    pub placement: Placement,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WavePhase {
+    Banner,
+    Spawning,
+    Breather,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct GroupProgress {
+    spawned: u32,
+    anchor: Option<(f32, f32)>, // Cluster centre
+    side: u8,                   // Edge or corner for line and corner groups
+    angle: f32,                 // Where a ring starts
+}
+
+impl SpawnPattern {
+    // Placement for popup `index` of the `total` a group spawns
+    fn placement(
+        self,
+        progress: &GroupProgress,
+        index: u32,
+        total: u32,
+        rng: &mut GameRng,
+    ) -> Placement {
+        let fraction = (index as f32 + 0.5) / total.max(1) as f32;
+        match self {
+            SpawnPattern::Random => Placement::Anywhere,
+            SpawnPattern::Cluster => {
+                let (ax, ay) = progress
+                    .anchor
+                    .unwrap_or((VIRTUAL_W / 2.0, VIRTUAL_H / 2.0));
+                let angle = rng.gen_range_f32(0.0, std::f32::consts::TAU);
+                let dist = rng.gen_range_f32(0.0, CLUSTER_SPREAD);
+                Placement::Near(ax + angle.cos() * dist, ay + angle.sin() * dist)
+            }
+            SpawnPattern::Edge => Placement::Edge,
+            SpawnPattern::Ring => Placement::Ring {
+                angle: progress.angle + fraction * std::f32::consts::TAU,
+            },
+            SpawnPattern::Line => Placement::Line {
+                side: progress.side,
+                t: fraction,
+            },
+            SpawnPattern::Corner => Placement::Corner {
+                corner: progress.side,
+            },
+        }
+    }
+}
+
+// Walks through a WaveScript: banner, spawning, breather, next wave
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WaveDirector {
+    pub wave_number: u32, // 1-based
+    pub phase: WavePhase,
+    pub phase_timer: f32,
+    pub boss_due: bool, // A boss wave started and its boss has not been spawned yet
+    groups: Vec<GroupProgress>,
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaveDirector {
+    pub fn new() -> Self {
+        Self {
+            wave_number: 1,
+            phase: WavePhase::Banner,
+            phase_timer: 0.0,
+            boss_due: false,
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn showing_banner(&self) -> bool {
+        self.phase == WavePhase::Banner
+    }
+
+    // Hand the pending boss over to the caller, once per boss wave
+    pub fn take_boss(&mut self) -> bool {
+        std::mem::take(&mut self.boss_due)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        dt: f32,
+        script: &WaveScript,
+        tuning: &Tuning,
+        level: &DifficultyLevel,
+        popups_alive: usize,
+        boss_alive: bool,
+        rng: &mut GameRng,
+    ) -> Vec<SpawnRequest> {
+        let mut requests = Vec::new();
+        self.phase_timer += dt;
+
+        match self.phase {
+            WavePhase::Banner => {
+                if self.phase_timer >= BANNER_TIME {
+                    let wave = script.wave(self.wave_number);
+                    self.groups = vec![GroupProgress::default(); wave.groups.len()];
+                    self.boss_due = script.is_boss_wave(self.wave_number);
+                    self.phase = WavePhase::Spawning;
+                    self.phase_timer = 0.0;
+                }
+            }
+            WavePhase::Spawning => {
+                let wave = script.wave(self.wave_number);
+                let mut last_spawn_time: f32 = 0.0;
+                let mut all_spawned = true;
+
+                for (group, progress) in wave.groups.iter().zip(self.groups.iter_mut()) {
+                    // Higher difficulty tightens the gaps between spawns
+                    let interval =
+                        group.interval.unwrap_or(tuning.spawn_interval) / level.spawn_rate;
+
+                    while progress.spawned < group.count
+                        && self.phase_timer >= group.delay + progress.spawned as f32 * interval
+                    {
+                        // Group-wide layout is picked when the first popup appears
+                        if progress.spawned == 0 {
+                            progress.anchor = Some((
+                                rng.gen_range_f32(CLUSTER_SPREAD, VIRTUAL_W - CLUSTER_SPREAD),
+                                rng.gen_range_f32(CLUSTER_SPREAD, VIRTUAL_H - CLUSTER_SPREAD),
+                            ));
+                            progress.side = rng.gen_range_usize(0, SIDES as usize) as u8;
+                            progress.angle = rng.gen_range_f32(0.0, std::f32::consts::TAU);
+                        }
+                        let total = group.count * level.spawn_count;
+                        for copy in 0..level.spawn_count {
+                            let index = progress.spawned * level.spawn_count + copy;
+                            let placement = group.pattern.placement(progress, index, total, rng);
+                            requests.push(SpawnRequest {
+                                shape: group.shape,
+                                behavior: group.behavior,
+                                placement,
+                            });
+                        }
+                        progress.spawned += 1;
+                    }
+
+                    if progress.spawned < group.count {
+                        all_spawned = false;
+                    }
+                    let group_end = group.delay + group.count.saturating_sub(1) as f32 * interval;
+                    last_spawn_time = last_spawn_time.max(group_end);
+                }
+
+                // A boss wave only ends with the boss
+                let boss_pending = boss_alive || self.boss_due;
+                let cleared = popups_alive == 0 && requests.is_empty();
+                if all_spawned
+                    && !boss_pending
+                    && (cleared || self.phase_timer >= last_spawn_time + wave.timeout)
+                {
+                    self.phase = WavePhase::Breather;
+                    self.phase_timer = 0.0;
+                }
+            }
+            WavePhase::Breather => {
+                let breather = script.wave(self.wave_number).breather / level.spawn_rate;
+                if self.phase_timer >= breather {
+                    self.wave_number += 1;
+                    self.phase = WavePhase::Banner;
+                    self.phase_timer = 0.0;
+                }
+            }
+        }
+
+        requests
+    }
+}
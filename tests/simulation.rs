@@ -0,0 +1,484 @@
+// Headless runs of the simulation driven by scripted input
+
+use cursor_crisis::behavior::BehaviorKind;
+use cursor_crisis::difficulty::DifficultyLevel;
+use cursor_crisis::modes::{GameMode, TIME_ATTACK_LENGTH};
+use cursor_crisis::pickups::{HEALTH_RESTORE, PICKUP_LIFETIME, Pickup, PickupKind};
+use cursor_crisis::snapshot;
+use cursor_crisis::timestep::SIM_DT;
+use cursor_crisis::tuning::Tuning;
+use cursor_crisis::waves::WaveScript;
+use cursor_crisis::{
+    FrameInput, GameState, InputSource, Popup, Replay, Scene, ScriptedInput, VIRTUAL_H, VIRTUAL_W,
+};
+
+const CURSOR: (f32, f32) = (400.0, 300.0);
+
+fn at_cursor() -> FrameInput {
+    FrameInput {
+        cursor_x: CURSOR.0,
+        cursor_y: CURSOR.1,
+        ..FrameInput::default()
+    }
+}
+
+fn explode_at_cursor() -> FrameInput {
+    FrameInput {
+        explode: true,
+        ..at_cursor()
+    }
+}
+
+// A run already underway, with nothing spawned yet
+fn playing(seed: u64, tuning: Tuning) -> GameState {
+    let mut game = GameState::new(seed, tuning);
+    game.start_run();
+    game
+}
+
+fn run(game: &mut GameState, frames: Vec<FrameInput>) {
+    let mut input = ScriptedInput::new(frames);
+    while !input.is_finished() {
+        game.update(SIM_DT, &input.poll());
+    }
+}
+
+// A chaser centred on (cx, cy)
+fn add_popup(game: &mut GameState, cx: f32, cy: f32) {
+    add_popup_of(game, BehaviorKind::Chaser, cx, cy);
+}
+
+fn add_popup_of(game: &mut GameState, kind: BehaviorKind, cx: f32, cy: f32) {
+    let mut popup = Popup::new(
+        VIRTUAL_W,
+        VIRTUAL_H,
+        kind,
+        &game.tuning,
+        &DifficultyLevel::default(),
+        &mut game.rng,
+    );
+    popup.move_center_to(cx, cy);
+    game.popups.push(popup);
+}
+
+#[test]
+fn explosion_costs_energy_and_regen_refills_it() {
+    let tuning = Tuning::default();
+    let mut game = playing(1, tuning.clone());
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert!((game.energy - (1.0 - tuning.energy_cost)).abs() < 1e-5);
+    assert_eq!(game.stats.explosions_used, 1);
+
+    let steps = 60;
+    run(&mut game, vec![at_cursor(); steps]);
+    let expected = 1.0 - tuning.energy_cost + tuning.energy_regen * SIM_DT * steps as f32;
+    assert!((game.energy - expected).abs() < 1e-4);
+}
+
+#[test]
+fn explosion_needs_enough_energy() {
+    let tuning = Tuning::default();
+    let mut game = playing(1, tuning.clone());
+    game.energy = tuning.energy_cost / 2.0;
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.stats.explosions_used, 0);
+    assert!(game.energy > tuning.energy_cost / 2.0);
+}
+
+#[test]
+fn touching_a_popup_drains_health() {
+    let tuning = Tuning::default();
+    let mut game = playing(2, tuning.clone());
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+
+    let steps = 30;
+    run(&mut game, vec![at_cursor(); steps]);
+    let expected = 1.0 - tuning.damage_rate * SIM_DT * steps as f32;
+    assert!((game.health - expected).abs() < 1e-4);
+}
+
+// Cursor sweeping across the arena, exploding twice a second
+fn scripted_session(seconds: f32) -> Vec<FrameInput> {
+    let steps = (seconds / SIM_DT) as usize;
+    (0..steps)
+        .map(|i| {
+            let t = i as f32 * SIM_DT;
+            FrameInput {
+                cursor_x: 400.0 + 250.0 * t.cos(),
+                cursor_y: 300.0 + 200.0 * (t * 1.3).sin(),
+                explode: i % 60 == 0,
+                ..FrameInput::default()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn same_seed_and_input_give_the_same_run() {
+    let frames = scripted_session(30.0);
+    let mut first = playing(42, Tuning::default());
+    let mut second = playing(42, Tuning::default());
+    run(&mut first, frames.clone());
+    run(&mut second, frames.clone());
+
+    assert!(first.stats.kills > 0);
+    assert_eq!(
+        snapshot::to_json(&first).unwrap(),
+        snapshot::to_json(&second).unwrap()
+    );
+
+    let mut other = playing(43, Tuning::default());
+    run(&mut other, frames);
+    assert_ne!(
+        snapshot::to_json(&first).unwrap(),
+        snapshot::to_json(&other).unwrap()
+    );
+}
+
+#[test]
+fn replaying_a_recording_rebuilds_the_run() {
+    let mut recorded = playing(9, Tuning::default());
+    let mut replay = Replay::new(
+        9,
+        GameMode::Endless,
+        Tuning::default(),
+        WaveScript::default(),
+    );
+    for frame in scripted_session(20.0) {
+        replay.record(&frame);
+        recorded.update(SIM_DT, &frame);
+    }
+
+    let replay = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    let mut replayed = playing(replay.seed, replay.tuning);
+    replayed.wave_script = replay.waves;
+    run(&mut replayed, replay.frames);
+    assert_eq!(
+        snapshot::to_json(&recorded).unwrap(),
+        snapshot::to_json(&replayed).unwrap()
+    );
+}
+
+// Popups that stay where they are put, with a fixed size and hit points
+fn still_popups(hp: f32) -> Tuning {
+    Tuning {
+        popup_size_min: 40.0,
+        popup_size_max: 40.0,
+        popup_speed_min: 0.0,
+        popup_speed_max: 0.0,
+        popup_hp_min: hp,
+        popup_hp_max: hp,
+        separation_strength: 0.0,
+        pulse_hitboxes: false,
+        pickup_drop_chance: 0.0,
+        ..Tuning::default()
+    }
+}
+
+#[test]
+fn blast_damage_falls_off_with_distance() {
+    let tuning = still_popups(2.5);
+    let mut game = playing(4, tuning.clone());
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+    // Edge 40 px from the centre of the blast
+    add_popup(&mut game, CURSOR.0 + 60.0, CURSOR.1);
+
+    run(&mut game, vec![explode_at_cursor()]);
+    let edge_damage = 1.0 - tuning.damage_falloff * 40.0 / tuning.explosion_radius;
+    assert!((game.popups[0].hp - (2.5 - tuning.explosion_damage)).abs() < 1e-4);
+    assert!((game.popups[1].hp - (2.5 - edge_damage * tuning.explosion_damage)).abs() < 1e-4);
+}
+
+#[test]
+fn tough_popups_survive_until_their_hit_points_run_out() {
+    let mut game = playing(4, still_popups(2.5));
+    add_popup(&mut game, CURSOR.0 + 60.0, CURSOR.1);
+
+    for blasts in 1..=2 {
+        run(&mut game, vec![explode_at_cursor()]);
+        assert_eq!(game.popups.len(), 1, "died after {} blasts", blasts);
+        assert!(game.popups[0].hit_flash > 0.0);
+    }
+    assert_eq!(game.stats.kills, 0);
+    run(&mut game, vec![explode_at_cursor()]);
+    assert!(game.popups.is_empty());
+    assert_eq!(game.stats.kills, 1);
+}
+
+#[test]
+fn explosion_kills_nearby_popups_and_scores() {
+    let mut game = playing(3, still_popups(0.5));
+    add_popup(&mut game, CURSOR.0 + 30.0, CURSOR.1);
+    add_popup(&mut game, CURSOR.0 - 30.0, CURSOR.1);
+    // Well outside the blast
+    add_popup(&mut game, CURSOR.0 + 300.0, CURSOR.1);
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.stats.kills, 2);
+    assert_eq!(game.popups.len(), 1);
+    // Two kills in one blast at x1: 1 + 2
+    assert_eq!(game.score, 3);
+    assert_eq!(game.combo.multiplier(), 2);
+}
+
+#[test]
+fn combo_multiplies_the_next_blast_and_damage_breaks_it() {
+    let mut game = playing(3, still_popups(0.5));
+    add_popup(&mut game, CURSOR.0 + 30.0, CURSOR.1);
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.score, 1);
+
+    add_popup(&mut game, CURSOR.0 + 30.0, CURSOR.1);
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.score, 1 + 2);
+    assert_eq!(game.combo.multiplier(), 3);
+
+    // Sitting on a popup costs the streak
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+    run(&mut game, vec![at_cursor()]);
+    assert_eq!(game.combo.multiplier(), 1);
+}
+
+// Steps of at-cursor input covering `seconds`
+fn wait(seconds: f32) -> Vec<FrameInput> {
+    vec![at_cursor(); (seconds / SIM_DT).ceil() as usize]
+}
+
+#[test]
+fn touching_a_pickup_collects_it() {
+    let mut game = playing(6, Tuning::default());
+    game.health = 0.5;
+    game.energy = 0.1;
+    game.pickups
+        .push(Pickup::new(CURSOR.0, CURSOR.1, PickupKind::Health));
+    game.pickups
+        .push(Pickup::new(CURSOR.0 + 5.0, CURSOR.1, PickupKind::Energy));
+    game.pickups
+        .push(Pickup::new(CURSOR.0, CURSOR.1 + 5.0, PickupKind::BigBlast));
+    // Out of reach
+    game.pickups
+        .push(Pickup::new(CURSOR.0 + 100.0, CURSOR.1, PickupKind::Shield));
+
+    run(&mut game, vec![at_cursor()]);
+    assert_eq!(game.pickups.len(), 1);
+    assert!((game.health - (0.5 + HEALTH_RESTORE)).abs() < 1e-5);
+    assert_eq!(game.energy, 1.0);
+    assert_eq!(game.effects.big_blast, PickupKind::BigBlast.duration());
+    assert_eq!(game.effects.shield, 0.0);
+    assert!(game.explosion_radius() > game.tuning.explosion_radius);
+}
+
+#[test]
+fn pickups_and_their_effects_run_out() {
+    let mut game = playing(6, Tuning::default());
+    game.pickups
+        .push(Pickup::new(CURSOR.0 + 100.0, CURSOR.1, PickupKind::Shield));
+    game.pickups
+        .push(Pickup::new(CURSOR.0, CURSOR.1, PickupKind::SlowTime));
+
+    run(&mut game, wait(PickupKind::SlowTime.duration() + 0.1));
+    assert_eq!(game.effects.slow_time, 0.0);
+    assert_eq!(game.pickups.len(), 1);
+    run(
+        &mut game,
+        wait(PICKUP_LIFETIME - PickupKind::SlowTime.duration()),
+    );
+    assert!(game.pickups.is_empty());
+}
+
+#[test]
+fn shield_blocks_contact_drain() {
+    let mut game = playing(7, Tuning::default());
+    game.effects.activate(PickupKind::Shield);
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+
+    run(&mut game, wait(1.0));
+    assert_eq!(game.health, 1.0);
+    assert!(game.effects.shield > 0.0);
+}
+
+#[test]
+fn destroyed_popups_drop_pickups() {
+    let tuning = Tuning {
+        pickup_drop_chance: 1.0,
+        ..still_popups(0.5)
+    };
+    let mut game = playing(8, tuning);
+    add_popup(&mut game, CURSOR.0 + 60.0, CURSOR.1);
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.pickups.len(), 1);
+    assert!((game.pickups[0].x - (CURSOR.0 + 60.0)).abs() < 1e-3);
+}
+
+#[test]
+fn chains_stop_at_the_depth_cap_and_score_deeper_links_higher() {
+    let tuning = still_popups(0.3);
+    assert_eq!(tuning.chain_max_depth, 2);
+    let mut game = playing(10, tuning);
+    // A line of popups: only the first is inside the player's blast and each
+    // of the others is in chain reach of the one before it
+    for i in 0..4 {
+        add_popup(&mut game, CURSOR.0 + 60.0 + 55.0 * i as f32, CURSOR.1);
+    }
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.stats.kills, 1);
+    run(&mut game, wait(1.0));
+    assert_eq!(game.stats.kills, 3);
+    assert_eq!(game.stats.longest_chain, 2);
+    // The last popup was a link too far
+    assert_eq!(game.popups.len(), 1);
+    assert!(game.chain_blasts.is_empty());
+    // Blast 1 at x1, then links worth 2 and 3 at the x2 it earned
+    assert_eq!(game.score, 1 + 2 * 2 + 3 * 2);
+}
+
+#[test]
+fn spawns_keep_clear_of_a_cursor_hugging_the_wall() {
+    let tuning = Tuning::default();
+    let mut game = GameState::new(11, tuning.clone());
+    game.mode = GameMode::Zen;
+    game.start_run();
+    let cursor = FrameInput {
+        cursor_x: 5.0,
+        cursor_y: 300.0,
+        ..FrameInput::default()
+    };
+
+    let mut spawned = 0;
+    for _ in 0..(60.0 / SIM_DT) as usize {
+        game.update(SIM_DT, &cursor);
+        // Spawns announced this step
+        for spawn in game.incoming.iter().filter(|s| s.timer <= SIM_DT * 1.5) {
+            let p = &spawn.popup;
+            let center_x = p.x + p.w / 2.0;
+            let center_y = p.y + p.h / 2.0;
+            let dist = (center_x - cursor.cursor_x).hypot(center_y - cursor.cursor_y);
+            assert!(
+                dist >= tuning.spawn_safe_distance - 1e-3,
+                "spawned {} px away",
+                dist
+            );
+            spawned += 1;
+        }
+    }
+    assert!(spawned > 10);
+}
+
+#[test]
+fn contact_follows_the_body_shape() {
+    // Inside a 20 px circle but past the edge of a 20 px diamond
+    let offset = 13.0;
+    for (kind, touching) in [(BehaviorKind::Chaser, true), (BehaviorKind::Flanker, false)] {
+        let mut game = playing(12, still_popups(1.0));
+        add_popup_of(&mut game, kind, CURSOR.0 + offset, CURSOR.1 + offset);
+        run(&mut game, vec![at_cursor()]);
+        assert_eq!(game.health < 1.0, touching, "{:?}", kind);
+    }
+}
+
+fn hold_charge(seconds: f32) -> Vec<FrameInput> {
+    let hold = FrameInput {
+        charge: true,
+        ..at_cursor()
+    };
+    vec![hold; (seconds / SIM_DT).ceil() as usize]
+}
+
+#[test]
+fn holding_charges_and_releasing_detonates_a_bigger_dearer_blast() {
+    let tuning = still_popups(0.3);
+    let mut game = playing(13, tuning.clone());
+    // Out of reach of a plain blast, inside a full charge
+    add_popup(&mut game, CURSOR.0 + 170.0, CURSOR.1);
+
+    run(&mut game, hold_charge(tuning.charge_time / 2.0));
+    assert!((game.charge_level() - 0.5).abs() < 0.01);
+    run(&mut game, hold_charge(tuning.charge_time));
+    assert_eq!(game.charge_level(), 1.0);
+    assert_eq!(game.stats.explosions_used, 0);
+    assert_eq!(game.energy, 1.0);
+
+    // Releasing the button sets it off
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.stats.explosions_used, 1);
+    assert!(game.charge.is_none());
+    let cost = tuning.energy_cost * tuning.charge_cost_scale;
+    assert!((game.energy - (1.0 - cost)).abs() < 1e-5);
+    assert_eq!(game.stats.kills, 1);
+}
+
+#[test]
+fn a_quick_click_is_a_plain_blast() {
+    let tuning = still_popups(0.3);
+    let mut game = playing(13, tuning.clone());
+    add_popup(&mut game, CURSOR.0 + 170.0, CURSOR.1);
+
+    run(&mut game, vec![explode_at_cursor()]);
+    assert_eq!(game.stats.explosions_used, 1);
+    assert!((game.energy - (1.0 - tuning.energy_cost)).abs() < 1e-5);
+    assert_eq!(game.stats.kills, 0);
+}
+
+#[test]
+fn charge_only_grows_while_energy_can_pay_for_it() {
+    let tuning = Tuning::default();
+    let mut game = playing(14, tuning.clone());
+    game.energy = 0.3;
+
+    // Long enough for a full charge, too short for regen to pay for one
+    run(&mut game, hold_charge(tuning.charge_time));
+    let level = game.charge_level();
+    assert!(level < 1.0);
+    assert!(game.charged_cost(level) <= game.energy);
+}
+
+fn playing_mode(mode: GameMode, tuning: Tuning) -> GameState {
+    let mut game = GameState::new(15, tuning);
+    game.mode = mode;
+    game.start_run();
+    game
+}
+
+#[test]
+fn one_hit_ends_the_run_before_a_health_pickup_can_save_it() {
+    let mut game = playing_mode(GameMode::OneHit, Tuning::default());
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+    game.pickups
+        .push(Pickup::new(CURSOR.0, CURSOR.1, PickupKind::Health));
+
+    run(&mut game, vec![at_cursor()]);
+    assert!(game.is_game_over());
+    assert_eq!(game.health, 0.0);
+    assert_eq!(game.pickups.len(), 1);
+}
+
+#[test]
+fn zen_takes_no_damage() {
+    let mut game = playing_mode(GameMode::Zen, Tuning::default());
+    add_popup(&mut game, CURSOR.0, CURSOR.1);
+
+    run(&mut game, wait(5.0));
+    assert_eq!(game.scene, Scene::Playing);
+    assert_eq!(game.health, 1.0);
+}
+
+#[test]
+fn time_attack_ends_when_the_clock_runs_out() {
+    // Nothing hurts, so only the clock can end it
+    let tuning = Tuning {
+        damage_rate: 0.0,
+        ..Tuning::default()
+    };
+    let mut game = playing_mode(GameMode::TimeAttack, tuning);
+
+    run(&mut game, wait(TIME_ATTACK_LENGTH - 0.5));
+    assert_eq!(game.scene, Scene::Playing);
+    assert!(game.time_left().unwrap() > 0.0);
+    run(&mut game, wait(1.0));
+    assert!(game.is_game_over());
+    assert_eq!(game.time_left(), Some(0.0));
+}